@@ -0,0 +1,536 @@
+use deluxe::SpannedValue;
+use heck::{ToKebabCase, ToSnakeCase};
+use proc_macro_error::{abort, emit_error};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+
+use std::collections::HashMap;
+
+use crate::{
+    generators::{variant_enum_impls, KeyGenerators, OverrideType},
+    schema::{Choice, Choices, Key, KeySignature, Range, Schema, SchemaList},
+    Auxiliaries, SettingsGenerator,
+};
+
+#[derive(deluxe::ParseAttributes)]
+#[deluxe(attributes(gsettings))]
+struct SchemaAttrs {
+    id: SpannedValue<String>,
+    path: Option<String>,
+    name: Option<SpannedValue<String>>,
+}
+
+#[derive(deluxe::ParseAttributes)]
+#[deluxe(attributes(gsettings))]
+struct KeyAttrs {
+    name: Option<String>,
+    signature: Option<String>,
+    default: String,
+    summary: Option<String>,
+    description: Option<String>,
+    min: Option<String>,
+    max: Option<String>,
+    #[deluxe(default)]
+    choices: Vec<String>,
+}
+
+#[derive(deluxe::ParseAttributes)]
+#[deluxe(attributes(gsettings))]
+struct EnumAttrs {
+    id: String,
+}
+
+#[derive(deluxe::ParseAttributes)]
+#[deluxe(attributes(gsettings))]
+struct EnumValueAttrs {
+    nick: Option<String>,
+}
+
+/// A part of the generated schema XML.
+enum XmlPart {
+    Text(String),
+    /// The id of the enum deriving `GSettingsSchema`, known only at runtime
+    EnumId(syn::Type),
+}
+
+pub fn derive(input: syn::DeriveInput) -> proc_macro2::TokenStream {
+    match input.data {
+        syn::Data::Struct(ref data) => derive_struct(&input, data),
+        syn::Data::Enum(ref data) => derive_enum(&input, data),
+        syn::Data::Union(_) => abort!(input.ident, "unions are not supported"),
+    }
+}
+
+fn derive_struct(input: &syn::DeriveInput, data: &syn::DataStruct) -> proc_macro2::TokenStream {
+    let SchemaAttrs { id, path, name } = deluxe::parse_attributes::<_, SchemaAttrs>(input)
+        .unwrap_or_else(|err| abort!(err.span(), err));
+    let id_span = id.span();
+    let id = SpannedValue::into_inner(id);
+
+    let syn::Fields::Named(ref fields) = data.fields else {
+        abort!(input.ident, "expected a struct with named fields");
+    };
+    let crate_path = crate::crate_path();
+
+    let mut keys = Vec::new();
+    let mut key_name_overrides = HashMap::new();
+    let mut enum_tys = Vec::new();
+    let mut enum_ty_assertions = Vec::new();
+    let mut field_keys = Vec::new();
+    let mut keys_xml = Vec::new();
+    for field in &fields.named {
+        let KeyAttrs {
+            name,
+            signature,
+            default,
+            summary,
+            description,
+            min,
+            max,
+            choices,
+        } = match deluxe::parse_attributes::<_, KeyAttrs>(field) {
+            Ok(key_attrs) => key_attrs,
+            Err(err) => {
+                emit_error!(err.span(), err);
+                continue;
+            }
+        };
+
+        let ty = &field.ty;
+        let ty_string = ty.to_token_stream().to_string();
+        let key_name =
+            name.unwrap_or_else(|| field.ident.as_ref().unwrap().to_string().to_kebab_case());
+
+        let (key_signature, arg_type) =
+            match signature.or_else(|| known_signature(ty).map(|(s, _)| s.to_string())) {
+                Some(signature) => {
+                    let arg_type = known_signature(ty)
+                        .map_or_else(|| ty_string.clone(), |(_, arg_type)| arg_type.to_string());
+                    (KeySignature::Type(signature), arg_type)
+                }
+                // Other types are expected to be enums deriving `GSettingsSchema`
+                None => {
+                    if !enum_tys.contains(ty) {
+                        enum_tys.push(ty.clone());
+                        enum_ty_assertions.push(quote_spanned! {ty.span()=>
+                            const _: () = #crate_path::__private::assert_schema_enum::<#ty>();
+                        });
+                    }
+                    (KeySignature::Enum(ty_string.clone()), ty_string.clone())
+                }
+            };
+
+        let mut key_xml = vec![XmlPart::Text(format!(
+            "        <key name=\"{}\" ",
+            escape(&key_name)
+        ))];
+        match key_signature {
+            KeySignature::Type(ref signature) => {
+                key_xml.push(XmlPart::Text(format!("type=\"{}\">\n", escape(signature))));
+            }
+            _ => {
+                key_xml.push(XmlPart::Text("enum=\"".to_string()));
+                key_xml.push(XmlPart::EnumId(ty.clone()));
+                key_xml.push(XmlPart::Text("\">\n".to_string()));
+            }
+        }
+        let mut key_xml_text = String::new();
+        if !choices.is_empty() {
+            key_xml_text.push_str("            <choices>\n");
+            for choice in &choices {
+                key_xml_text.push_str(&format!(
+                    "                <choice value=\"{}\"/>\n",
+                    escape(choice)
+                ));
+            }
+            key_xml_text.push_str("            </choices>\n");
+        }
+        if min.is_some() || max.is_some() {
+            key_xml_text.push_str("            <range");
+            if let Some(ref min) = min {
+                key_xml_text.push_str(&format!(" min=\"{}\"", escape(min)));
+            }
+            if let Some(ref max) = max {
+                key_xml_text.push_str(&format!(" max=\"{}\"", escape(max)));
+            }
+            key_xml_text.push_str("/>\n");
+        }
+        key_xml_text.push_str(&format!(
+            "            <default>{}</default>\n",
+            escape(&default)
+        ));
+        if let Some(ref summary) = summary {
+            key_xml_text.push_str(&format!(
+                "            <summary>{}</summary>\n",
+                escape(summary)
+            ));
+        }
+        if let Some(ref description) = description {
+            key_xml_text.push_str(&format!(
+                "            <description>{}</description>\n",
+                escape(description)
+            ));
+        }
+        key_xml_text.push_str("        </key>\n");
+        key_xml.push(XmlPart::Text(key_xml_text));
+        keys_xml.extend(key_xml);
+
+        let mut key = Key::new(key_name.clone(), key_signature, default);
+        key.summary = summary;
+        key.description = description;
+        key.range = (min.is_some() || max.is_some()).then_some(Range { min, max });
+        key.choices = (!choices.is_empty()).then(|| Choices {
+            choices: choices.into_iter().map(|value| Choice { value }).collect(),
+        });
+        keys.push(key);
+        field_keys.push((
+            field.ident.as_ref().unwrap(),
+            arg_type.clone(),
+            key_name.clone(),
+        ));
+
+        key_name_overrides.insert(
+            key_name,
+            OverrideType::Define {
+                arg_type,
+                ret_type: ty_string,
+                conversion: None,
+                serde: None,
+            },
+        );
+    }
+
+    // Generate the accessors
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let settings_ident = if let Some(name) = name {
+        crate::parse_ident(&name, name.span())
+    } else {
+        format_ident!("{}Settings", ident)
+    };
+
+    let mut xml_parts = vec![XmlPart::Text(format!(
+        "    <schema {}id=\"{}\">\n",
+        path.as_ref()
+            .map(|path| format!("path=\"{}\" ", escape(path)))
+            .unwrap_or_default(),
+        escape(&id)
+    ))];
+    xml_parts.extend(keys_xml);
+    xml_parts.push(XmlPart::Text("    </schema>\n".to_string()));
+
+    let schema_list = SchemaList {
+        enums: Vec::new(),
+        flags: Vec::new(),
+        schemas: vec![Schema {
+            id: id.clone(),
+            path,
+            extends: None,
+            keys,
+            children: Vec::new(),
+            overrides: Vec::new(),
+        }],
+    };
+    let schema = &schema_list.schemas[0];
+
+    let mut key_generators = KeyGenerators::with_defaults(HashMap::new(), HashMap::new());
+    key_generators.add_key_name_overrides(key_name_overrides);
+
+    let mut generator = SettingsGenerator {
+        schema_list: &schema_list,
+        struct_idents: HashMap::from([(schema.id.as_str(), &settings_ident)]),
+        key_generators: &key_generators,
+        aux_visibility: vis,
+        aux: Auxiliaries::default(),
+    };
+    let settings_struct_docs = format!("Typed settings for the `{}` schema of [`{}`].", id, ident);
+    let settings_struct = quote! {
+        #[doc = #settings_struct_docs]
+        #vis struct #settings_ident(gio::Settings);
+    };
+    let settings_token_stream = generator.generate(
+        &settings_struct,
+        &settings_ident,
+        schema,
+        Some(syn::LitStr::new(&id, id_span).to_token_stream()),
+    );
+
+    // Generate the schema XML
+    let push_xml_parts = xml_parts.iter().map(|part| match part {
+        XmlPart::Text(text) => quote! { xml.push_str(#text); },
+        XmlPart::EnumId(ty) => {
+            quote! { xml.push_str(<#ty as #crate_path::__private::SchemaEnum>::ID); }
+        }
+    });
+    let schema_file_name = format!("{}.gschema.xml", id);
+
+    // Generate the conversions of the struct from and to the settings with
+    // the fallible accessors
+    let load_fields = field_keys.iter().map(|(field_ident, _, key_name)| {
+        let try_getter_func_ident = format_ident!("try_{}", key_name.to_snake_case());
+        quote! { #field_ident: settings.#try_getter_func_ident()? }
+    });
+    let store_fields = field_keys.iter().map(|(field_ident, arg_type, key_name)| {
+        let try_setter_func_ident = format_ident!("try_set_{}", key_name.to_snake_case());
+        let value = match arg_type.as_str() {
+            "&[&str]" => quote! {
+                &std::iter::Iterator::collect::<Vec<&str>>(self.#field_ident.iter().map(String::as_str))
+            },
+            arg_type if arg_type.starts_with('&') => quote! { &self.#field_ident },
+            _ => quote! { std::clone::Clone::clone(&self.#field_ident) },
+        };
+        quote! { settings.#try_setter_func_ident(#value)?; }
+    });
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        #(#enum_ty_assertions)*
+
+        #settings_token_stream
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The id of the schema.
+            pub const SCHEMA_ID: &'static str = #id;
+
+            /// Returns the values of all keys in the given settings.
+            pub fn load(settings: &#settings_ident) -> std::result::Result<Self, gio::glib::BoolError> {
+                Ok(Self {
+                    #(#load_fields),*
+                })
+            }
+
+            /// Sets all keys in the given settings to the values of the fields.
+            pub fn store(&self, settings: &#settings_ident) -> std::result::Result<(), gio::glib::BoolError> {
+                #(#store_fields)*
+                Ok(())
+            }
+
+            /// Returns the schema XML, including the definitions of the enums used
+            /// by the keys.
+            pub fn schema_xml() -> String {
+                let mut enum_xmls: Vec<&str> = Vec::new();
+                #(
+                    if !enum_xmls.contains(&<#enum_tys as #crate_path::__private::SchemaEnum>::XML) {
+                        enum_xmls.push(<#enum_tys as #crate_path::__private::SchemaEnum>::XML);
+                    }
+                )*
+
+                let mut xml = String::new();
+                xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<schemalist>\n");
+                for enum_xml in enum_xmls {
+                    xml.push_str(enum_xml);
+                }
+                #(#push_xml_parts)*
+                xml.push_str("</schemalist>\n");
+                xml
+            }
+
+            /// Writes [`Self::schema_xml`] to a `.gschema.xml` file named after
+            /// the schema id in the given directory, so it can be compiled
+            /// and installed, and returns the path of the written file.
+            pub fn write_schema_file(dir: impl AsRef<std::path::Path>) -> std::io::Result<std::path::PathBuf> {
+                let path = dir.as_ref().join(#schema_file_name);
+                std::fs::write(&path, Self::schema_xml())?;
+                Ok(path)
+            }
+        }
+    }
+}
+
+fn derive_enum(input: &syn::DeriveInput, data: &syn::DataEnum) -> proc_macro2::TokenStream {
+    let EnumAttrs { id } = deluxe::parse_attributes::<_, EnumAttrs>(input)
+        .unwrap_or_else(|err| abort!(err.span(), err));
+
+    let variants = enum_variants(data, |variant| {
+        deluxe::parse_attributes::<_, EnumValueAttrs>(variant)
+            .map(|EnumValueAttrs { nick }| nick)
+            .map_err(|err| emit_error!(err.span(), err))
+    });
+
+    let mut xml = format!("    <enum id=\"{}\">\n", escape(&id));
+    for (nick, value, _) in &variants {
+        xml.push_str(&format!(
+            "        <value nick=\"{}\" value=\"{}\"/>\n",
+            escape(nick),
+            value
+        ));
+    }
+    xml.push_str("    </enum>\n");
+
+    let ident = &input.ident;
+    let variant_impls = variant_enum_impls(
+        ident,
+        &variants
+            .iter()
+            .map(|(nick, _, variant_ident)| (nick.as_str(), *variant_ident))
+            .collect::<Vec<_>>(),
+    );
+
+    let crate_path = crate::crate_path();
+
+    quote! {
+        #variant_impls
+
+        impl #ident {
+            /// The id of the enum in the schema.
+            pub const SCHEMA_ENUM_ID: &'static str = #id;
+
+            /// The `<enum>` definition of the enum in the schema.
+            pub const SCHEMA_XML: &'static str = #xml;
+        }
+
+        impl #crate_path::__private::SchemaEnum for #ident {
+            const ID: &'static str = Self::SCHEMA_ENUM_ID;
+            const XML: &'static str = Self::SCHEMA_XML;
+        }
+    }
+}
+
+pub fn derive_settings_enum(input: syn::DeriveInput) -> proc_macro2::TokenStream {
+    let syn::Data::Enum(ref data) = input.data else {
+        abort!(input.ident, "expected an enum");
+    };
+
+    let variants = enum_variants(data, |variant| {
+        let mut nick = None;
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("nick"))
+        {
+            match attr.meta.require_name_value().map(|meta| &meta.value) {
+                Ok(syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit_str),
+                    ..
+                })) if nick.is_none() => nick = Some(lit_str.value()),
+                Ok(_) if nick.is_some() => emit_error!(attr, "duplicate nick"),
+                _ => emit_error!(attr, "expected `#[nick = \"..\"]`"),
+            }
+        }
+        Ok(nick)
+    });
+
+    let ident = &input.ident;
+    let variant_impls = variant_enum_impls(
+        ident,
+        &variants
+            .iter()
+            .map(|(nick, _, variant_ident)| (nick.as_str(), *variant_ident))
+            .collect::<Vec<_>>(),
+    );
+    let nicks = variants.iter().map(|(nick, _, _)| nick);
+    let values = variants.iter().map(|(_, value, _)| value);
+    let crate_path = crate::crate_path();
+
+    quote! {
+        #variant_impls
+
+        impl #crate_path::SettingsEnum for #ident {
+            const VALUES: &'static [(&'static str, i32)] = &[#((#nicks, #values)),*];
+        }
+    }
+}
+
+/// Returns the nick, value, and ident of each variant, emitting errors for the
+/// invalid ones. The nick is read with `variant_nick`, which emits its own
+/// errors, and defaults to the ident in kebab case, while the value follows
+/// the discriminants like in Rust.
+fn enum_variants(
+    data: &syn::DataEnum,
+    variant_nick: impl Fn(&syn::Variant) -> Result<Option<String>, ()>,
+) -> Vec<(String, i32, &syn::Ident)> {
+    let mut variants = Vec::new();
+    let mut next_value = Some(0);
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            emit_error!(variant.fields, "expected a unit variant");
+            continue;
+        }
+
+        let Ok(nick) = variant_nick(variant) else {
+            continue;
+        };
+
+        let value = match variant.discriminant {
+            Some((_, ref expr)) => match discriminant_value(expr) {
+                Some(value) => value,
+                None => {
+                    emit_error!(
+                        expr,
+                        "expected an integer literal within the range of `i32`"
+                    );
+                    continue;
+                }
+            },
+            None => match next_value {
+                Some(value) => value,
+                None => {
+                    emit_error!(variant, "the value of the variant overflows `i32`");
+                    continue;
+                }
+            },
+        };
+        next_value = value.checked_add(1);
+
+        let nick = nick.unwrap_or_else(|| variant.ident.to_string().to_kebab_case());
+        if variants
+            .iter()
+            .any(|(other_nick, _, _)| *other_nick == nick)
+        {
+            emit_error!(variant, "duplicate nick `{}`", nick);
+            continue;
+        }
+
+        variants.push((nick, value, &variant.ident));
+    }
+    variants
+}
+
+/// Returns the signature and the setter parameter type for the given field type.
+fn known_signature(ty: &syn::Type) -> Option<(&'static str, &'static str)> {
+    let ty_string = ty.to_token_stream().to_string().replace(' ', "");
+
+    Some(match ty_string.as_str() {
+        "bool" => ("b", "bool"),
+        "u8" => ("y", "u8"),
+        "i16" => ("n", "i16"),
+        "u16" => ("q", "u16"),
+        "i32" => ("i", "i32"),
+        "u32" => ("u", "u32"),
+        "i64" => ("x", "i64"),
+        "u64" => ("t", "u64"),
+        "f64" => ("d", "f64"),
+        "String" => ("s", "&str"),
+        "Vec<String>" => ("as", "&[&str]"),
+        "PathBuf" | "std::path::PathBuf" => ("ay", "&std::path::Path"),
+        _ => return None,
+    })
+}
+
+/// Returns the value of the given discriminant, which is parsed as an `i64`
+/// first, so that `i32::MIN` can be negated, and then range-checked.
+fn discriminant_value(expr: &syn::Expr) -> Option<i32> {
+    fn int_value(expr: &syn::Expr) -> Option<i64> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            }) => lit.base10_parse().ok(),
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Neg(_),
+                expr,
+                ..
+            }) => int_value(expr).map(|value| -value),
+            _ => None,
+        }
+    }
+
+    int_value(expr).and_then(|value| i32::try_from(value).ok())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
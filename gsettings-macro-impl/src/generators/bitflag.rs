@@ -0,0 +1,308 @@
+use heck::{ToPascalCase, ToShoutySnakeCase};
+use proc_macro2::Span;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+use super::{parse_type, Context, KeyGenerator, SchemaFlag, SchemaKey};
+
+pub fn key_generator<'a>(
+    key: &'a SchemaKey,
+    flag: &SchemaFlag,
+    aux_visibility: syn::Visibility,
+    glib_type: bool,
+) -> KeyGenerator<'a> {
+    let (flag_name, flag_token_stream) = flags_token_stream(flag, aux_visibility, glib_type);
+    KeyGenerator::new(key, Context::new_with_aux(&flag_name, flag_token_stream))
+}
+
+/// Uses a user-defined flags type that implements `SettingsFlags`, with a
+/// compile-time check that its nicks and values match the definition.
+pub fn typed_key_generator<'a>(
+    key: &'a SchemaKey,
+    flag: &SchemaFlag,
+    flag_type: &str,
+) -> KeyGenerator<'a> {
+    let type_ = parse_type(flag_type);
+    let nicks = flag.values.iter().map(|value| value.nick.as_str());
+    let values = flag.values.iter().map(|value| value.value);
+
+    let message = format!(
+        "the nicks and values of `{}` do not match the `{}` flags in the schema, which are {}",
+        flag_type,
+        flag.id,
+        flag.values
+            .iter()
+            .map(|value| format!("`{}` = {}", value.nick, value.value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let crate_path = crate::crate_path();
+    let check_token_stream = quote! {
+        const _: () = #crate_path::__private::check_flags_values::<#type_>(
+            &[#((#nicks, #values)),*],
+            #message,
+        );
+    };
+
+    KeyGenerator::new(key, Context::new_with_aux(flag_type, check_token_stream))
+}
+
+/// Returns the name of the bitflags generated for the definition and its tokens.
+pub fn flags_token_stream(
+    flag: &SchemaFlag,
+    visibility: syn::Visibility,
+    glib_type: bool,
+) -> (String, proc_macro2::TokenStream) {
+    let flag_name = super::type_name_from_id(&flag.id);
+    let glib_type_name = glib_type.then(|| super::glib_type_name_from_id(&flag.id));
+    let flag_token_stream =
+        bitflag_token_stream(&flag_name, flag, visibility, glib_type_name.as_deref());
+    (flag_name, flag_token_stream)
+}
+
+/// If a GLib type name is given, the flags are also registered as a GLib
+/// flags type with the nicks as the value nicks, like `glib::flags` does.
+fn bitflag_token_stream(
+    name: &str,
+    flag: &SchemaFlag,
+    visibility: syn::Visibility,
+    glib_type_name: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let value_idents = flag
+        .values
+        .iter()
+        .map(|value| Ident::new(&value.nick.to_shouty_snake_case(), value.nick.span()))
+        .collect::<Vec<_>>();
+
+    let flags_arms = value_idents
+        .iter()
+        .zip(flag.values.iter())
+        .map(|(value_ident, value)| {
+            let value = value.value;
+            quote! {
+                const #value_ident = #value;
+            }
+        });
+
+    let from_variant_arms =
+        value_idents
+            .iter()
+            .zip(flag.values.iter())
+            .map(|(value_ident, value)| {
+                let nick = &value.nick;
+                quote! {
+                    #nick => this.insert(Self::#value_ident)
+                }
+            });
+
+    let to_variant_arms =
+        value_idents
+            .iter()
+            .zip(flag.values.iter())
+            .map(|(value_ident, value)| {
+                let nick = &value.nick;
+                quote! {
+                    if self.contains(Self::#value_ident) {
+                        string_array.push(#nick)
+                    }
+                }
+            });
+
+    let nicks = flag.values.iter().map(|value| value.nick.as_str());
+    let values = flag.values.iter().map(|value| value.value);
+
+    let name_pascal_case = name.to_pascal_case();
+    let ident = Ident::new(&name_pascal_case, Span::call_site());
+    let docs = format!("The `{}` flags of the schema.", flag.id);
+    let id = &flag.id;
+
+    let glib_impls = glib_type_name
+        .map(|glib_type_name| glib_flags_impls(&ident, glib_type_name, flag, &value_idents));
+
+    let crate_path = crate::crate_path();
+
+    quote! {
+        gio::glib::bitflags::bitflags! {
+            #[doc = #docs]
+            #[doc(alias = #id)]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #visibility struct #ident: u32 {
+                #(#flags_arms)*
+            }
+        }
+
+        #glib_impls
+
+        impl #crate_path::SettingsFlags for #ident {
+            const VALUES: &'static [(&'static str, u32)] = &[#((#nicks, #values)),*];
+        }
+
+        impl gio::glib::variant::StaticVariantType for #ident {
+            fn static_variant_type() -> std::borrow::Cow<'static, gio::glib::VariantTy> {
+                std::borrow::Cow::Borrowed(gio::glib::VariantTy::STRING_ARRAY)
+            }
+        }
+
+        impl gio::glib::variant::FromVariant for #ident {
+            fn from_variant(variant: &gio::glib::Variant) -> Option<Self> {
+                let mut this = Self::empty();
+
+                for string in variant.get::<Vec<String>>()? {
+                    match string.as_str() {
+                        #(#from_variant_arms),*,
+                        _ => return None,
+                    }
+                }
+
+                Some(this)
+            }
+        }
+
+        impl gio::glib::variant::ToVariant for #ident {
+            fn to_variant(&self) -> gio::glib::Variant {
+                let mut string_array = Vec::new();
+
+                #(#to_variant_arms)*
+
+                gio::glib::variant::ToVariant::to_variant(&string_array)
+            }
+        }
+
+        impl std::convert::From<#ident> for gio::glib::Variant {
+            fn from(this: #ident) -> gio::glib::Variant {
+                gio::glib::variant::ToVariant::to_variant(&this)
+            }
+        }
+    }
+}
+
+/// Registers the flags as a static GLib flags type on first use, which is
+/// what `glib::flags` does, except that it keeps the items of the
+/// `bitflags!` definition, like its docs.
+fn glib_flags_impls(
+    ident: &Ident,
+    glib_type_name: &str,
+    flag: &SchemaFlag,
+    value_idents: &[Ident],
+) -> proc_macro2::TokenStream {
+    let flags_values = value_idents
+        .iter()
+        .zip(flag.values.iter())
+        .map(|(value_ident, value)| {
+            let value_name = format!("{}\0", value.nick.to_pascal_case());
+            let value_nick = format!("{}\0", value.nick);
+            quote! {
+                gio::glib::gobject_ffi::GFlagsValue {
+                    value: #ident::#value_ident.bits(),
+                    value_name: #value_name.as_ptr() as *const _,
+                    value_nick: #value_nick.as_ptr() as *const _,
+                },
+            }
+        });
+    // The values are terminated by a zeroed one
+    let n_values = flag.values.len() + 1;
+
+    let type_name = format!("{}\0", glib_type_name);
+    let already_registered_message = format!(
+        "GLib type `{}` is already registered; the `{}` flags must be generated with `glib_types` only once in the program",
+        glib_type_name, flag.id
+    );
+
+    quote! {
+        impl gio::glib::prelude::StaticType for #ident {
+            fn static_type() -> gio::glib::Type {
+                struct Values([gio::glib::gobject_ffi::GFlagsValue; #n_values]);
+
+                // SAFETY: The values only point to static strings and are never mutated
+                unsafe impl Sync for Values {}
+
+                static VALUES: Values = Values([
+                    #(#flags_values)*
+                    gio::glib::gobject_ffi::GFlagsValue {
+                        value: 0,
+                        value_name: std::ptr::null(),
+                        value_nick: std::ptr::null(),
+                    },
+                ]);
+                static TYPE: std::sync::OnceLock<gio::glib::Type> = std::sync::OnceLock::new();
+
+                *TYPE.get_or_init(|| unsafe {
+                    let type_name = #type_name.as_ptr() as *const _;
+                    assert_eq!(
+                        gio::glib::gobject_ffi::g_type_from_name(type_name),
+                        gio::glib::gobject_ffi::G_TYPE_INVALID,
+                        #already_registered_message
+                    );
+
+                    let type_: gio::glib::Type = gio::glib::translate::from_glib(
+                        gio::glib::gobject_ffi::g_flags_register_static(type_name, VALUES.0.as_ptr()),
+                    );
+                    assert!(type_.is_valid());
+                    type_
+                })
+            }
+        }
+
+        impl gio::glib::translate::IntoGlib for #ident {
+            type GlibType = u32;
+
+            fn into_glib(self) -> u32 {
+                self.bits()
+            }
+        }
+
+        impl gio::glib::translate::FromGlib<u32> for #ident {
+            unsafe fn from_glib(value: u32) -> Self {
+                Self::from_bits_truncate(value)
+            }
+        }
+
+        impl gio::glib::value::ValueType for #ident {
+            type Type = Self;
+        }
+
+        unsafe impl<'a> gio::glib::value::FromValue<'a> for #ident {
+            type Checker = gio::glib::value::GenericValueTypeChecker<Self>;
+
+            unsafe fn from_value(value: &'a gio::glib::Value) -> Self {
+                gio::glib::translate::from_glib(gio::glib::gobject_ffi::g_value_get_flags(
+                    gio::glib::translate::ToGlibPtr::to_glib_none(value).0,
+                ))
+            }
+        }
+
+        impl gio::glib::value::ToValue for #ident {
+            fn to_value(&self) -> gio::glib::Value {
+                let mut value = gio::glib::Value::for_value_type::<Self>();
+                unsafe {
+                    gio::glib::gobject_ffi::g_value_set_flags(
+                        gio::glib::translate::ToGlibPtrMut::to_glib_none_mut(&mut value).0,
+                        gio::glib::translate::IntoGlib::into_glib(*self),
+                    );
+                }
+                value
+            }
+
+            fn value_type(&self) -> gio::glib::Type {
+                <Self as gio::glib::prelude::StaticType>::static_type()
+            }
+        }
+
+        impl std::convert::From<#ident> for gio::glib::Value {
+            fn from(this: #ident) -> gio::glib::Value {
+                gio::glib::value::ToValue::to_value(&this)
+            }
+        }
+
+        impl gio::glib::HasParamSpec for #ident {
+            type ParamSpec = gio::glib::ParamSpecFlags;
+            type SetValue = Self;
+            type BuilderFn = fn(&str) -> gio::glib::ParamSpecFlagsBuilder<'_, Self>;
+
+            fn param_spec_builder() -> Self::BuilderFn {
+                |name| gio::glib::ParamSpecFlags::builder(name)
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::{Context, KeyGenerator, SchemaKey, ValueConversion};
+
+/// The types used for keys with `ay` type signature, which are usually
+/// bytestrings, i.e., strings with a trailing nul byte, or raw bytes.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum BytestringType {
+    #[default]
+    Path,
+    OsString,
+    Bytes,
+    RawBytes,
+}
+
+impl BytestringType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "path" => Some(Self::Path),
+            "os_string" => Some(Self::OsString),
+            "bytes" => Some(Self::Bytes),
+            "raw_bytes" => Some(Self::RawBytes),
+            _ => None,
+        }
+    }
+}
+
+pub fn key_generator(key: &SchemaKey, bytestring_type: BytestringType) -> KeyGenerator<'_> {
+    KeyGenerator::new(key, context(bytestring_type))
+}
+
+pub fn context(bytestring_type: BytestringType) -> Context {
+    // The variant conversions of `Path` and `OsStr` already handle the trailing
+    // nul byte, while raw bytes are stored as is, so they can also hold binary data
+    match bytestring_type {
+        BytestringType::Path => Context::new_dissimilar("&std::path::Path", "std::path::PathBuf"),
+        BytestringType::OsString => {
+            Context::new_dissimilar("&std::ffi::OsStr", "std::ffi::OsString")
+        }
+        BytestringType::Bytes => {
+            let crate_path = crate::crate_path();
+            Context::new_with_value_conversion(
+                "&[u8]",
+                "Vec<u8>",
+                Some(ValueConversion {
+                    get_with: syn::parse_quote!(#crate_path::__private::bytestring::from_bytestring),
+                    set_with: syn::parse_quote!(#crate_path::__private::bytestring::to_bytestring),
+                    is_fallible: false,
+                }),
+            )
+            .with_native(context(BytestringType::RawBytes))
+        }
+        BytestringType::RawBytes => Context::new_dissimilar("&[u8]", "Vec<u8>"),
+    }
+}
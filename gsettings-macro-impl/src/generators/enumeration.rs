@@ -0,0 +1,74 @@
+use quote::quote;
+
+use super::{parse_type, Context, EnumDefault, KeyGenerator, SchemaEnum, SchemaKey};
+
+pub fn key_generator<'a>(
+    key: &'a SchemaKey,
+    enum_: &SchemaEnum,
+    aux_visibility: syn::Visibility,
+    glib_type: bool,
+    default: Option<&EnumDefault>,
+) -> KeyGenerator<'a> {
+    let (enum_name, enum_token_stream) =
+        enum_token_stream(enum_, aux_visibility, glib_type, default);
+    KeyGenerator::new(key, Context::new_with_aux(&enum_name, enum_token_stream))
+}
+
+/// Uses a user-defined enum that implements `SettingsEnum`, with a
+/// compile-time check that its nicks and values match the definition.
+pub fn typed_key_generator<'a>(
+    key: &'a SchemaKey,
+    enum_: &SchemaEnum,
+    enum_type: &str,
+) -> KeyGenerator<'a> {
+    let type_ = parse_type(enum_type);
+    let nicks = enum_.values.iter().map(|value| value.nick.as_str());
+    let values = enum_.values.iter().map(|value| value.value);
+
+    let message = format!(
+        "the nicks and values of `{}` do not match the `{}` enum in the schema, which are {}",
+        enum_type,
+        enum_.id,
+        enum_
+            .values
+            .iter()
+            .map(|value| format!("`{}` = {}", value.nick, value.value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let crate_path = crate::crate_path();
+    let check_token_stream = quote! {
+        const _: () = #crate_path::__private::check_enum_values::<#type_>(
+            &[#((#nicks, #values)),*],
+            #message,
+        );
+    };
+
+    KeyGenerator::new(key, Context::new_with_aux(enum_type, check_token_stream))
+}
+
+/// Returns the name of the enum generated for the definition and its tokens,
+/// which implements `Default` with the `default` nick, if any.
+pub fn enum_token_stream(
+    enum_: &SchemaEnum,
+    visibility: syn::Visibility,
+    glib_type: bool,
+    default: Option<&EnumDefault>,
+) -> (String, proc_macro2::TokenStream) {
+    let enum_name = super::type_name_from_id(&enum_.id);
+    let glib_type_name = glib_type.then(|| super::glib_type_name_from_id(&enum_.id));
+    let enum_token_stream = super::new_variant_enum(
+        &enum_name,
+        Some(&enum_.id),
+        &enum_
+            .values
+            .iter()
+            .map(|value| (value.nick.as_str(), Some(value.value)))
+            .collect::<Vec<_>>(),
+        visibility,
+        glib_type_name.as_deref(),
+        default,
+    );
+    (enum_name, enum_token_stream)
+}
@@ -0,0 +1,1202 @@
+mod bitflag;
+mod bytestring;
+mod enumeration;
+mod named_struct;
+mod string;
+mod variant_type;
+
+use heck::{ToPascalCase, ToSnakeCase};
+use proc_macro2::Span;
+use proc_macro_error::{abort_call_site, emit_call_site_warning};
+use quote::{format_ident, quote};
+use std::collections::{HashMap, HashSet};
+use syn::Ident;
+
+use std::fmt::Write;
+
+pub use self::bytestring::BytestringType;
+
+use crate::schema::{
+    Enum as SchemaEnum, Flag as SchemaFlag, Key as SchemaKey, KeySignature as SchemaKeySignature,
+    Schema, SchemaList,
+};
+
+pub enum OverrideType {
+    Define {
+        arg_type: String,
+        ret_type: String,
+        conversion: Option<ValueConversion>,
+        serde: Option<SerdeFormat>,
+    },
+    Struct(NamedStruct),
+    Skip,
+}
+
+/// A struct generated for a key with tuple type, with a field for each
+/// element of the tuple.
+#[derive(Clone)]
+pub struct NamedStruct {
+    /// Defaults to the key name in pascal case
+    pub name: Option<syn::Ident>,
+    /// Defaults to the names listed in the key's summary
+    pub fields: Option<Vec<syn::Ident>>,
+}
+
+/// User-provided functions that convert the key's own type to the defined
+/// return type, and the defined parameter type to the key's own type.
+#[derive(Clone)]
+pub struct ValueConversion {
+    pub get_with: syn::Path,
+    pub set_with: syn::Path,
+    /// Whether the functions return a `Result`
+    pub is_fallible: bool,
+}
+
+/// How values of a type that implements serde's traits are stored.
+#[derive(Clone, Copy)]
+pub enum SerdeFormat {
+    /// As a JSON string in a key with `s` type signature
+    Json,
+    /// As a variant of the key's type
+    Variant,
+}
+
+impl SerdeFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "variant" => Some(Self::Variant),
+            _ => None,
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum GetResult<'a> {
+    Some(KeyGenerator<'a>),
+    Skip,
+    Unknown,
+}
+
+pub struct KeyGenerators<'a> {
+    signatures: HashMap<SchemaKeySignature, Context>,
+    key_names: HashMap<String, Context>,
+    signature_structs: HashMap<SchemaKeySignature, NamedStruct>,
+    key_name_structs: HashMap<String, NamedStruct>,
+    enums: HashMap<String, &'a SchemaEnum>,
+    flags: HashMap<String, &'a SchemaFlag>,
+    /// Enum or flags id to the user-defined type used instead of a generated one
+    enum_types: HashMap<String, String>,
+    /// Enum id to the default shared by its keys
+    enum_defaults: HashMap<String, EnumDefault>,
+    /// Name of the enum generated for string keys with choices to the
+    /// default shared by its keys
+    choice_defaults: HashMap<String, EnumDefault>,
+    signature_skips: HashSet<SchemaKeySignature>,
+    key_name_skips: HashSet<String>,
+    bytestring_type: BytestringType,
+    glib_types: bool,
+}
+
+impl<'a> KeyGenerators<'a> {
+    pub fn with_defaults(
+        enums: HashMap<String, &'a SchemaEnum>,
+        flags: HashMap<String, &'a SchemaFlag>,
+    ) -> Self {
+        Self {
+            signatures: HashMap::new(),
+            key_names: HashMap::new(),
+            signature_structs: HashMap::new(),
+            key_name_structs: HashMap::new(),
+            enums,
+            flags,
+            enum_types: HashMap::new(),
+            enum_defaults: HashMap::new(),
+            choice_defaults: HashMap::new(),
+            signature_skips: HashSet::new(),
+            key_name_skips: HashSet::new(),
+            bytestring_type: BytestringType::default(),
+            glib_types: false,
+        }
+    }
+
+    /// Sets the types used for keys with `ay` type signature.
+    pub fn set_bytestring_type(&mut self, bytestring_type: BytestringType) {
+        self.bytestring_type = bytestring_type;
+    }
+
+    /// Uses the given types, instead of generated ones, for the keys of
+    /// the enums and flags with the mapped ids.
+    pub fn add_enum_types(&mut self, enum_types: HashMap<String, String>) {
+        self.enum_types.extend(enum_types);
+    }
+
+    /// Sets the defaults of the generated enums, which implement `Default`
+    /// with them.
+    pub fn set_enum_defaults(&mut self, enum_defaults: HashMap<String, EnumDefault>) {
+        self.enum_defaults = enum_defaults;
+    }
+
+    /// Sets the defaults of the enums generated for the string keys with
+    /// choices of the given schemas, which are shared by all the keys with
+    /// the same name. This must be called after adding the overrides, as
+    /// overridden keys don't use the generated enums.
+    pub fn set_choice_defaults<'b>(&mut self, schemas: impl IntoIterator<Item = &'b Schema>) {
+        let mut key_defaults = HashMap::<String, Vec<(&str, &str, &str)>>::new();
+
+        for schema in schemas {
+            for key in &schema.keys {
+                if key.choices.is_none() || !self.uses_string_generator(key) {
+                    continue;
+                }
+
+                key_defaults
+                    .entry(key.name.to_pascal_case())
+                    .or_default()
+                    .push((&key.name, &schema.id, key.default.trim()));
+            }
+        }
+
+        self.choice_defaults = key_defaults
+            .into_iter()
+            .map(|(enum_name, key_defaults)| {
+                let default = shared_default(&enum_name, &key_defaults);
+                (enum_name, default)
+            })
+            .collect();
+    }
+
+    /// Sets whether the generated enums and flags are registered as GLib types.
+    pub fn set_glib_types(&mut self, glib_types: bool) {
+        self.glib_types = glib_types;
+    }
+
+    /// Add contexts that has higher priority than default, but lower than
+    /// key_name overrides
+    pub fn add_signature_overrides(
+        &mut self,
+        overrides: HashMap<SchemaKeySignature, OverrideType>,
+    ) {
+        for (signature, item) in overrides {
+            match item {
+                OverrideType::Define {
+                    arg_type,
+                    ret_type,
+                    conversion,
+                    serde,
+                } => {
+                    self.signatures.insert(
+                        signature,
+                        Context::new_with_value_conversion(&arg_type, &ret_type, conversion)
+                            .with_serde(serde),
+                    );
+                }
+                OverrideType::Struct(named_struct) => {
+                    self.signature_structs.insert(signature, named_struct);
+                }
+                OverrideType::Skip => {
+                    self.signature_skips.insert(signature);
+                }
+            }
+        }
+    }
+
+    /// Add contexts that has higher priority than both default and signature overrides.
+    pub fn add_key_name_overrides(&mut self, overrides: HashMap<String, OverrideType>) {
+        for (key_name, item) in overrides {
+            match item {
+                OverrideType::Define {
+                    arg_type,
+                    ret_type,
+                    conversion,
+                    serde,
+                } => {
+                    self.key_names.insert(
+                        key_name,
+                        Context::new_with_value_conversion(&arg_type, &ret_type, conversion)
+                            .with_serde(serde),
+                    );
+                }
+                OverrideType::Struct(named_struct) => {
+                    self.key_name_structs.insert(key_name, named_struct);
+                }
+                OverrideType::Skip => {
+                    self.key_name_skips.insert(key_name);
+                }
+            }
+        }
+    }
+
+    /// Returns the context of the key's own type, ignoring the overrides.
+    fn native_context(&self, key: &SchemaKey) -> Context {
+        match key.signature() {
+            Some(SchemaKeySignature::Type(type_)) => match type_.as_str() {
+                "s" => Context::new_dissimilar("&str", "String"),
+                "ay" => bytestring::context(self.bytestring_type),
+                signature => variant_type::context(signature).unwrap_or_else(|| {
+                    abort_call_site!(
+                        "unsupported `{}` type used by key `{}` with `get_with` and `set_with`",
+                        signature,
+                        key.name
+                    )
+                }),
+            },
+            _ => abort_call_site!(
+                "`get_with` and `set_with` are only supported on keys with `type`; found key `{}`",
+                key.name
+            ),
+        }
+    }
+
+    /// Returns whether the key is of `s` type signature and not overridden
+    /// nor skipped, which is when `string::key_generator` is used.
+    fn uses_string_generator(&self, key: &SchemaKey) -> bool {
+        let signature = SchemaKeySignature::Type("s".to_string());
+
+        key.signature().as_ref() == Some(&signature)
+            && !self.key_name_skips.contains(&key.name)
+            && !self.signature_skips.contains(&signature)
+            && !self.key_names.contains_key(&key.name)
+            && !self.key_name_structs.contains_key(&key.name)
+            && !self.signatures.contains_key(&signature)
+            && !self.signature_structs.contains_key(&signature)
+    }
+
+    fn defined_key_generator(&self, key: &'a SchemaKey, context: &Context) -> KeyGenerator<'a> {
+        let mut context = context.clone();
+
+        if context.value_conversion.is_some() {
+            context.native = Some(Box::new(self.native_context(key)));
+        }
+
+        if let Some(serde) = context.serde {
+            check_serde_format(key, serde);
+        }
+
+        KeyGenerator::new(key, context)
+    }
+
+    pub fn get(
+        &'a self,
+        key: &'a SchemaKey,
+        aux_visibility: syn::Visibility,
+    ) -> Option<GetResult<'a>> {
+        let key_signature = key.signature()?;
+
+        if self.key_name_skips.contains(&key.name) {
+            return Some(GetResult::Skip);
+        }
+
+        if self.signature_skips.contains(&key_signature) {
+            return Some(GetResult::Skip);
+        }
+
+        if let Some(context) = self.key_names.get(&key.name) {
+            return Some(GetResult::Some(self.defined_key_generator(key, context)));
+        }
+
+        if let Some(named_struct) = self.key_name_structs.get(&key.name) {
+            return Some(GetResult::Some(named_struct::key_generator(
+                key,
+                named_struct,
+                aux_visibility,
+            )));
+        }
+
+        if let Some(context) = self.signatures.get(&key_signature) {
+            return Some(GetResult::Some(self.defined_key_generator(key, context)));
+        }
+
+        if let Some(named_struct) = self.signature_structs.get(&key_signature) {
+            return Some(GetResult::Some(named_struct::key_generator(
+                key,
+                named_struct,
+                aux_visibility,
+            )));
+        }
+
+        Some(match key_signature {
+            SchemaKeySignature::Type(type_) => match type_.as_str() {
+                "s" => GetResult::Some(string::key_generator(
+                    key,
+                    aux_visibility,
+                    self.choice_defaults.get(&key.name.to_pascal_case()),
+                )),
+                "ay" => GetResult::Some(bytestring::key_generator(key, self.bytestring_type)),
+                signature => variant_type::key_generator(key, signature)
+                    .map_or(GetResult::Unknown, GetResult::Some),
+            },
+            SchemaKeySignature::Enum(ref enum_name) => {
+                let enum_ = self.enums.get(enum_name).unwrap_or_else(|| {
+                    abort_call_site!("expected an enum definition for `{}`", enum_name)
+                });
+
+                GetResult::Some(match self.enum_types.get(enum_name) {
+                    Some(enum_type) => enumeration::typed_key_generator(key, enum_, enum_type),
+                    None => enumeration::key_generator(
+                        key,
+                        enum_,
+                        aux_visibility,
+                        self.glib_types,
+                        self.enum_defaults.get(enum_name),
+                    ),
+                })
+            }
+            SchemaKeySignature::Flag(ref flag_name) => {
+                let flag = self.flags.get(flag_name).unwrap_or_else(|| {
+                    abort_call_site!("expected a flag definition for `{}`", flag_name)
+                });
+
+                GetResult::Some(match self.enum_types.get(flag_name) {
+                    Some(flag_type) => bitflag::typed_key_generator(key, flag, flag_type),
+                    None => bitflag::key_generator(key, flag, aux_visibility, self.glib_types),
+                })
+            }
+        })
+    }
+}
+
+pub struct KeyGenerator<'a> {
+    key: &'a SchemaKey,
+    context: Context,
+}
+
+impl<'a> KeyGenerator<'a> {
+    /// Returns the name of the auxiliary type and its definition, if any.
+    pub fn auxiliary(&self) -> Option<(&str, proc_macro2::TokenStream)> {
+        self.context
+            .auxiliary
+            .clone()
+            .map(|auxiliary| (self.context.ret_type.as_str(), auxiliary))
+    }
+
+    fn new(key: &'a SchemaKey, context: Context) -> Self {
+        Self { key, context }
+    }
+
+    fn func_docs(&self) -> proc_macro2::TokenStream {
+        let mut stream = proc_macro2::TokenStream::new();
+
+        let has_summary = self
+            .key
+            .summary
+            .as_ref()
+            .is_some_and(|summary| !summary.is_empty());
+
+        let has_description = self
+            .key
+            .description
+            .as_ref()
+            .is_some_and(|description| !description.is_empty());
+
+        if has_summary {
+            let summary = self.key.summary.as_ref().unwrap();
+            stream.extend(quote! {
+                #[doc = #summary]
+            });
+        }
+
+        if has_summary && has_description {
+            stream.extend(quote! {
+                #[doc = ""]
+            });
+        }
+
+        if has_description {
+            let description = self.key.description.as_ref().unwrap();
+            stream.extend(quote! {
+                #[doc = #description]
+            });
+        }
+
+        let default_docs = format!("default: {}", self.key.default);
+        stream.extend(quote! {
+            #[doc = ""]
+            #[doc = #default_docs]
+        });
+
+        // only needed for numerical types
+        if let Some(ref range) = self.key.range {
+            let has_min = range.min.as_ref().is_some_and(|min| !min.is_empty());
+            let has_max = range.max.as_ref().is_some_and(|max| !max.is_empty());
+
+            if has_min || has_max {
+                stream.extend(quote! {
+                    #[doc = ""]
+                });
+            }
+            let mut range_docs = String::new();
+            if has_min {
+                write!(range_docs, "min: {}", range.min.as_ref().unwrap()).unwrap();
+            }
+            if has_min && has_max {
+                range_docs.push(';');
+                range_docs.push(' ');
+            }
+            if has_max {
+                write!(range_docs, "max: {}", range.max.as_ref().unwrap()).unwrap();
+            }
+            stream.extend(quote! {
+                #[doc = #range_docs]
+            })
+        }
+
+        stream
+    }
+}
+
+impl quote::ToTokens for KeyGenerator<'_> {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(self.token_stream(false));
+    }
+}
+
+impl KeyGenerator<'_> {
+    /// Returns the names of the generated methods.
+    pub fn func_names(&self) -> Vec<String> {
+        let getter_func_name = self.key.name.to_snake_case();
+
+        vec![
+            format!("connect_{}_changed", getter_func_name),
+            format!("bind_{}", getter_func_name),
+            format!("create_{}_action", getter_func_name),
+            format!("reset_{}", getter_func_name),
+            format!("set_{}", getter_func_name),
+            format!("try_set_{}", getter_func_name),
+            format!("try_{}", getter_func_name),
+            format!("{}_default_value", getter_func_name),
+            getter_func_name,
+        ]
+    }
+
+    /// Generates the methods as provided methods of a trait, which has
+    /// `settings` and `from_settings` required methods.
+    pub fn trait_token_stream(&self) -> proc_macro2::TokenStream {
+        self.token_stream(true)
+    }
+
+    fn token_stream(&self, is_trait_item: bool) -> proc_macro2::TokenStream {
+        let mut tokens = proc_macro2::TokenStream::new();
+
+        let (vis, settings, from_settings) = if is_trait_item {
+            (
+                quote! {},
+                quote! { self.settings() },
+                quote! { Self::from_settings(gio::Settings::clone(settings)) },
+            )
+        } else {
+            (
+                quote! { pub },
+                quote! { &self.0 },
+                quote! { Self(gio::Settings::clone(settings)) },
+            )
+        };
+
+        let key_name = self.key.name.as_str();
+        let key_name_snake_case = key_name.to_snake_case();
+        let getter_func_ident = Ident::new(&key_name_snake_case, Span::call_site());
+
+        let connect_changed_func_ident = format_ident!("connect_{}_changed", getter_func_ident);
+        let bind_func_ident = format_ident!("bind_{}", getter_func_ident);
+        let create_action_func_ident = format_ident!("create_{}_action", getter_func_ident);
+        let reset_func_ident = format_ident!("reset_{}", getter_func_ident);
+
+        let func_docs = self.func_docs();
+
+        tokens.extend(quote! {
+            #func_docs
+            #vis fn #connect_changed_func_ident(&self, f: impl Fn(&Self) + 'static) -> gio::glib::SignalHandlerId {
+                gio::prelude::SettingsExt::connect_changed(#settings, Some(#key_name), move |settings, _| {
+                    f(&#from_settings)
+                })
+            }
+
+            #func_docs
+            #vis fn #bind_func_ident<'a>(&'a self, object: &'a impl gio::glib::object::IsA<gio::glib::Object>, property: &'a str) -> gio::BindingBuilder<'a> {
+                gio::prelude::SettingsExtManual::bind(#settings, #key_name, object, property)
+            }
+
+            #func_docs
+            #vis fn #create_action_func_ident(&self) -> gio::Action {
+                gio::prelude::SettingsExt::create_action(#settings, #key_name)
+            }
+
+            #func_docs
+            #vis fn #reset_func_ident(&self) {
+                gio::prelude::SettingsExt::reset(#settings, #key_name);
+            }
+        });
+
+        let setter_func_ident = format_ident!("set_{}", getter_func_ident);
+        let try_setter_func_ident = format_ident!("try_set_{}", getter_func_ident);
+        let try_getter_func_ident = format_ident!("try_{}", getter_func_ident);
+        let default_value_func_ident = format_ident!("{}_default_value", getter_func_ident);
+
+        let get_type = parse_type(&self.context.ret_type);
+        let set_type = parse_type(&self.context.arg_type);
+        let set_generics = &self.context.set_generics;
+        let map_err = quote! {
+            .map_err(|err| gio::glib::bool_error!("failed to convert value for key `{}`: {}", #key_name, err))
+        };
+        let crate_path = crate::crate_path();
+
+        tokens.extend(quote! {
+            #func_docs
+            #vis fn #setter_func_ident #set_generics(&self, value: #set_type) {
+                self.#try_setter_func_ident(value).unwrap_or_else(|err| panic!("failed to set value for key `{}`: {:?}", #key_name, err))
+            }
+        });
+
+        let default_value = quote! {
+            gio::prelude::SettingsExt::default_value(#settings, #key_name).unwrap()
+        };
+
+        if let Some(serde) = self.context.serde {
+            let (to, from) = match serde {
+                SerdeFormat::Json => (quote! { to_json }, quote! { from_json }),
+                SerdeFormat::Variant => (quote! { to_variant }, quote! { from_variant }),
+            };
+            let type_arg = match serde {
+                SerdeFormat::Json => None,
+                SerdeFormat::Variant => {
+                    let type_ = match self.key.signature() {
+                        Some(SchemaKeySignature::Type(type_)) => type_,
+                        _ => unreachable!("checked in `KeyGenerators::get`"),
+                    };
+                    Some(quote! { , gio::glib::VariantTy::new(#type_).unwrap() })
+                }
+            };
+
+            tokens.extend(quote! {
+                #func_docs
+                #vis fn #try_setter_func_ident #set_generics(&self, value: #set_type) -> std::result::Result<(), gio::glib::BoolError> {
+                    let value = #crate_path::__private::serde::#to(&value #type_arg)#map_err?;
+                    gio::prelude::SettingsExtManual::set(#settings, #key_name, &value)
+                }
+
+                #func_docs
+                #vis fn #try_getter_func_ident(&self) -> std::result::Result<#get_type, gio::glib::BoolError> {
+                    #crate_path::__private::serde::require_feature!();
+                    #crate_path::__private::serde::#from(&gio::prelude::SettingsExt::value(#settings, #key_name))#map_err
+                }
+
+                #func_docs
+                #vis fn #getter_func_ident(&self) -> #get_type {
+                    self.#try_getter_func_ident().unwrap_or_else(|err| panic!("failed to get value for key `{}`: {:?}", #key_name, err))
+                }
+
+                #func_docs
+                #vis fn #default_value_func_ident(&self) -> #get_type {
+                    #crate_path::__private::serde::#from(&#default_value)#map_err.unwrap_or_else(|err| panic!("failed to get default value for key `{}`: {:?}", #key_name, err))
+                }
+            });
+
+            return tokens;
+        }
+
+        let (Some(value_conversion), Some(native)) =
+            (&self.context.value_conversion, &self.context.native)
+        else {
+            let set_conversion = self.context.settings_value_set_conversion();
+
+            tokens.extend(quote! {
+                #func_docs
+                #vis fn #try_setter_func_ident #set_generics(&self, value: #set_type) -> std::result::Result<(), gio::glib::BoolError> {
+                    #set_conversion
+                    gio::prelude::SettingsExtManual::set(#settings, #key_name, &value)
+                }
+
+                #func_docs
+                #vis fn #try_getter_func_ident(&self) -> std::result::Result<#get_type, gio::glib::BoolError> {
+                    let value: #get_type = #crate_path::__private::from_variant(&gio::prelude::SettingsExt::value(#settings, #key_name))#map_err?;
+                    Ok(value)
+                }
+
+                #func_docs
+                #vis fn #getter_func_ident(&self) -> #get_type {
+                    self.#try_getter_func_ident().unwrap_or_else(|err| panic!("failed to get value for key `{}`: {:?}", #key_name, err))
+                }
+
+                #func_docs
+                #vis fn #default_value_func_ident(&self) -> #get_type {
+                    let value: #get_type = #crate_path::__private::from_variant(&#default_value).unwrap_or_else(|err| panic!("failed to get default value for key `{}`: {:?}", #key_name, err));
+                    value
+                }
+            });
+
+            return tokens;
+        };
+
+        // Convert between the key's own type and the defined type with the
+        // user-provided functions
+        let ValueConversion {
+            get_with,
+            set_with,
+            is_fallible,
+        } = value_conversion.as_ref();
+        let native_type = parse_type(&native.ret_type);
+
+        let (set_value, get_value) = if *is_fallible {
+            (
+                quote! { #set_with(value)#map_err? },
+                quote! { #get_with(value)#map_err },
+            )
+        } else {
+            (quote! { #set_with(value) }, quote! { Ok(#get_with(value)) })
+        };
+
+        tokens.extend(quote! {
+            #func_docs
+            #vis fn #try_setter_func_ident #set_generics(&self, value: #set_type) -> std::result::Result<(), gio::glib::BoolError> {
+                let value: #native_type = #set_value;
+                let value = gio::glib::variant::ToVariant::to_variant(&value);
+                gio::prelude::SettingsExtManual::set(#settings, #key_name, &value)
+            }
+
+            #func_docs
+            #vis fn #try_getter_func_ident(&self) -> std::result::Result<#get_type, gio::glib::BoolError> {
+                let value: #native_type = #crate_path::__private::from_variant(&gio::prelude::SettingsExt::value(#settings, #key_name))#map_err?;
+                #get_value
+            }
+
+            #func_docs
+            #vis fn #getter_func_ident(&self) -> #get_type {
+                self.#try_getter_func_ident().unwrap_or_else(|err| panic!("failed to get value for key `{}`: {:?}", #key_name, err))
+            }
+
+            #func_docs
+            #vis fn #default_value_func_ident(&self) -> #get_type {
+                let value = || -> std::result::Result<#get_type, gio::glib::BoolError> {
+                    let value: #native_type = #crate_path::__private::from_variant(&#default_value)#map_err?;
+                        #get_value
+                };
+                value().unwrap_or_else(|err| panic!("failed to get default value for key `{}`: {:?}", #key_name, err))
+            }
+        });
+
+        tokens
+    }
+}
+
+#[derive(Clone)]
+pub struct Context {
+    arg_type: String,
+    ret_type: String,
+    auxiliary: Option<proc_macro2::TokenStream>,
+    /// Statements that convert the `value` parameter to a type that can be
+    /// passed by reference to `SettingsExtManual::set`
+    set_conversion: Option<proc_macro2::TokenStream>,
+    /// Generic parameters of the setters, used by the parameter type
+    set_generics: Option<proc_macro2::TokenStream>,
+    value_conversion: Option<Box<ValueConversion>>,
+    /// The context of the key's own type, which is converted from and to
+    /// with `value_conversion`
+    native: Option<Box<Context>>,
+    serde: Option<SerdeFormat>,
+}
+
+impl Context {
+    pub fn new_dissimilar(arg_type: &str, ret_type: &str) -> Self {
+        Self {
+            arg_type: arg_type.to_string(),
+            ret_type: ret_type.to_string(),
+            auxiliary: None,
+            set_conversion: None,
+            set_generics: None,
+            value_conversion: None,
+            native: None,
+            serde: None,
+        }
+    }
+
+    pub fn new_with_aux(type_: &str, auxiliary: proc_macro2::TokenStream) -> Self {
+        Self {
+            auxiliary: Some(auxiliary),
+            ..Self::new_dissimilar(type_, type_)
+        }
+    }
+
+    pub fn new_with_conversion(
+        arg_type: &str,
+        ret_type: &str,
+        set_conversion: proc_macro2::TokenStream,
+    ) -> Self {
+        Self {
+            set_conversion: Some(set_conversion),
+            ..Self::new_dissimilar(arg_type, ret_type)
+        }
+    }
+
+    pub fn new_with_value_conversion(
+        arg_type: &str,
+        ret_type: &str,
+        value_conversion: Option<ValueConversion>,
+    ) -> Self {
+        Self {
+            value_conversion: value_conversion.map(Box::new),
+            ..Self::new_dissimilar(arg_type, ret_type)
+        }
+    }
+
+    /// Returns the statements that convert the `value` parameter to a variant
+    /// with `SettingsValue`. If the parameter type is the return type or a
+    /// reference to it, it is converted with the return type's
+    /// `SettingsValue::to_variant`. Otherwise, like `&str` for `String`, the
+    /// parameter, after the `set_conversion` if any, is converted as is and
+    /// checked against the return type's `SettingsValue::variant_type`.
+    fn settings_value_set_conversion(&self) -> proc_macro2::TokenStream {
+        use quote::ToTokens;
+
+        let arg_type = parse_type(&self.arg_type);
+        let ret_type = parse_type(&self.ret_type);
+        let is_ret_type = |type_: &syn::Type| {
+            type_.to_token_stream().to_string() == ret_type.to_token_stream().to_string()
+        };
+        let crate_path = crate::crate_path();
+
+        let value = match arg_type {
+            _ if self.set_conversion.is_some() => None,
+            syn::Type::Reference(ref reference)
+                if reference.mutability.is_none() && is_ret_type(&reference.elem) =>
+            {
+                Some(quote! { value })
+            }
+            _ if is_ret_type(&arg_type) => Some(quote! { &value }),
+            _ => None,
+        };
+
+        match value {
+            Some(value) => quote! {
+                let value = #crate_path::__private::to_variant::<#ret_type>(#value)?;
+            },
+            None => {
+                let set_conversion = &self.set_conversion;
+                quote! {
+                    #set_conversion
+                    let value = #crate_path::__private::param_to_variant::<#ret_type>(&value)?;
+                }
+            }
+        }
+    }
+
+    pub fn with_serde(mut self, serde: Option<SerdeFormat>) -> Self {
+        self.serde = serde;
+        self
+    }
+
+    pub fn with_native(mut self, native: Context) -> Self {
+        self.native = Some(Box::new(native));
+        self
+    }
+
+    pub fn with_set_generics(mut self, set_generics: proc_macro2::TokenStream) -> Self {
+        self.set_generics = Some(set_generics);
+        self
+    }
+}
+
+/// Aborts if the key can't store values in the given serde format.
+fn check_serde_format(key: &SchemaKey, serde: SerdeFormat) {
+    match (serde, key.signature()) {
+        (SerdeFormat::Json, Some(SchemaKeySignature::Type(type_))) if type_ == "s" => {}
+        (SerdeFormat::Variant, Some(SchemaKeySignature::Type(_))) => {}
+        (SerdeFormat::Json, _) => abort_call_site!(
+            "`serde = \"json\"` is only supported on keys with `s` type; found key `{}`",
+            key.name
+        ),
+        (SerdeFormat::Variant, _) => abort_call_site!(
+            "`serde = \"variant\"` is only supported on keys with `type`; found key `{}`",
+            key.name
+        ),
+    }
+}
+
+fn parse_type(type_: &str) -> syn::Type {
+    syn::parse_str::<syn::Type>(type_).unwrap_or_else(|_| panic!("Invalid type `{}`", type_))
+}
+
+/// Generates a type for each enum and flags definition in the schema list,
+/// without any key, which are also registered as GLib types if `glib_types`
+/// is set.
+pub fn types_token_stream(
+    schema_list: &SchemaList,
+    visibility: syn::Visibility,
+    glib_types: bool,
+) -> proc_macro2::TokenStream {
+    let enum_defaults = enum_defaults(&schema_list.schemas, &HashMap::new());
+    let enums = schema_list.enums.iter().map(|enum_| {
+        let default = enum_defaults.get(&enum_.id);
+        enumeration::enum_token_stream(enum_, visibility.clone(), glib_types, default).1
+    });
+    let flags = schema_list
+        .flags
+        .iter()
+        .map(|flag| bitflag::flags_token_stream(flag, visibility.clone(), glib_types).1);
+
+    quote! {
+        #(#enums)*
+        #(#flags)*
+    }
+}
+
+/// Returns the name of the type generated for an enum or flags definition,
+/// which is the last component of its id in pascal case (e.g., `AlertSound`
+/// for `io.github.seadve.AlertSound`).
+pub fn type_name_from_id(id: &str) -> String {
+    id.rsplit('.').next().unwrap_or(id).to_pascal_case()
+}
+
+/// Returns the name of the GLib type registered for an enum or flags
+/// definition, which is its id in pascal case (e.g., `IoGithubSeadveAlertSound`
+/// for `io.github.seadve.AlertSound`), as GLib type names can't contain dots.
+pub fn glib_type_name_from_id(id: &str) -> String {
+    id.split('.').map(|part| part.to_pascal_case()).collect()
+}
+
+/// Creates an enum with given name and (variant name, variant value) tuple. It implements
+/// [`FromVariant`](gio::glib::variant::FromVariant), [`ToVariant`](gio::glib::variant::ToVariant),
+/// and [`StaticVariantType`](gio::glib::variant::StaticVariantType), along with
+/// `Display` and `FromStr` using the variant names as nicks, `TryFrom<i32>`
+/// if the values are given, and `Default` if a default variant name is given.
+/// If the default is conflicting instead, its note is added to the documentation.
+///
+/// The input names are converted to pascal case. The id of the schema enum, if
+/// any, is added to the documentation and as an alias. If a GLib type name is
+/// given, the enum is also registered as a GLib enum type with the input names
+/// as the value nicks.
+fn new_variant_enum(
+    name: &str,
+    id: Option<&str>,
+    variants: &[(&str, Option<i32>)],
+    visibility: syn::Visibility,
+    glib_type_name: Option<&str>,
+    default: Option<&EnumDefault>,
+) -> proc_macro2::TokenStream {
+    use syn::spanned::Spanned;
+
+    let variant_names = variants
+        .iter()
+        .map(|(variant_name, _)| variant_name)
+        .collect::<Vec<_>>();
+
+    let variant_idents = variant_names
+        .iter()
+        .map(|variant_name| Ident::new(&variant_name.to_pascal_case(), variant_name.span()))
+        .collect::<Vec<_>>();
+
+    let variant_arms = variants.iter().zip(variant_idents.iter()).map(
+        |((variant_name, variant_value), variant_ident)| {
+            let enum_value = glib_type_name.map(|_| {
+                quote! {
+                    #[enum_value(nick = #variant_name)]
+                }
+            });
+
+            if let Some(variant_value) = variant_value {
+                quote! {
+                    #enum_value
+                    #variant_ident = #variant_value
+                }
+            } else {
+                quote! {
+                    #enum_value
+                    #variant_ident
+                }
+            }
+        },
+    );
+
+    let name_pascal_case = name.to_pascal_case();
+    let ident = Ident::new(&name_pascal_case, name_pascal_case.span());
+
+    let docs = id.map(|id| {
+        let docs = format!("The `{}` enum of the schema.", id);
+        quote! {
+            #[doc = #docs]
+            #[doc(alias = #id)]
+        }
+    });
+
+    let default_docs = match default {
+        Some(EnumDefault::Conflicting(note)) => {
+            let separator = id.map(|_| quote! { #[doc = ""] });
+            Some(quote! {
+                #separator
+                #[doc = #note]
+            })
+        }
+        _ => None,
+    };
+
+    let variant_impls = variant_enum_impls(
+        &ident,
+        &variant_names
+            .iter()
+            .zip(variant_idents.iter())
+            .map(|(variant_name, variant_ident)| (**variant_name, variant_ident))
+            .collect::<Vec<_>>(),
+    );
+
+    let glib_enum = glib_type_name.map(|glib_type_name| {
+        quote! {
+            #[derive(gio::glib::Enum)]
+            #[enum_type(name = #glib_type_name)]
+        }
+    });
+
+    let n_variants = variant_idents.len();
+
+    let nick_arms =
+        variant_names
+            .iter()
+            .zip(variant_idents.iter())
+            .map(|(variant_name, variant_ident)| {
+                quote! {
+                    Self::#variant_ident => #variant_name
+                }
+            });
+
+    let from_nick_arms =
+        variant_names
+            .iter()
+            .zip(variant_idents.iter())
+            .map(|(variant_name, variant_ident)| {
+                quote! {
+                    #variant_name => Some(Self::#variant_ident)
+                }
+            });
+
+    let try_from_impl = variants
+        .iter()
+        .map(|(_, variant_value)| *variant_value)
+        .collect::<Option<Vec<_>>>()
+        .map(|variant_values| {
+            quote! {
+                impl std::convert::TryFrom<i32> for #ident {
+                    type Error = gio::glib::BoolError;
+
+                    fn try_from(value: i32) -> Result<Self, Self::Error> {
+                        match value {
+                            #(#variant_values => Ok(Self::#variant_idents),)*
+                            _ => Err(gio::glib::bool_error!("invalid value `{}` for `{}`", value, #name_pascal_case)),
+                        }
+                    }
+                }
+            }
+        });
+
+    let default_impl = match default {
+        Some(EnumDefault::Nick(nick)) => variant_names.iter().position(|name| *name == nick),
+        _ => None,
+    }
+    .map(|index| {
+        let variant_ident = &variant_idents[index];
+        quote! {
+            impl std::default::Default for #ident {
+                /// The default value of the key in the schema.
+                fn default() -> Self {
+                    Self::#variant_ident
+                }
+            }
+        }
+    });
+
+    quote! {
+        #docs
+        #default_docs
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        #glib_enum
+        #[repr(i32)]
+        #visibility enum #ident {
+            #(#variant_arms),*
+        }
+
+        impl #ident {
+            /// All the variants, in the order of the schema.
+            pub const ALL: [Self; #n_variants] = [#(Self::#variant_idents),*];
+
+            /// Returns an iterator over all the variants, in the order of the schema.
+            pub fn iter() -> impl Iterator<Item = Self> {
+                Self::ALL.into_iter()
+            }
+
+            /// Returns the nick of the variant in the schema.
+            pub fn nick(self) -> &'static str {
+                match self {
+                    #(#nick_arms),*
+                }
+            }
+
+            /// Returns the variant with the given nick in the schema, if any.
+            pub fn from_nick(nick: &str) -> Option<Self> {
+                match nick {
+                    #(#from_nick_arms),*,
+                    _ => None,
+                }
+            }
+        }
+
+        impl std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.nick())
+            }
+        }
+
+        impl std::str::FromStr for #ident {
+            type Err = gio::glib::BoolError;
+
+            fn from_str(nick: &str) -> Result<Self, Self::Err> {
+                Self::from_nick(nick).ok_or_else(|| {
+                    gio::glib::bool_error!("invalid nick `{}` for `{}`", nick, #name_pascal_case)
+                })
+            }
+        }
+
+        #try_from_impl
+
+        #default_impl
+
+        #variant_impls
+    }
+}
+
+/// Returns the nick of a string default value in GVariant text format,
+/// like `'bark'`.
+fn default_nick(default: &str) -> Option<&str> {
+    let default = default.trim();
+
+    ['\'', '"']
+        .into_iter()
+        .find_map(|quote| default.strip_prefix(quote)?.strip_suffix(quote))
+        .filter(|nick| !nick.contains('\\'))
+}
+
+/// The default of a generated enum, which is shared by all the keys that use it.
+#[derive(Clone, Debug)]
+pub enum EnumDefault {
+    /// The nick of the default value of every key, used to implement `Default`
+    Nick(String),
+    /// The note added to the documentation of the enum, explaining why it
+    /// doesn't implement `Default`
+    Conflicting(String),
+}
+
+/// Returns the default of each enum definition that is shared by all the keys
+/// of the given schemas that refer to it, whose defaults already include the
+/// overrides of the schemas. Definitions mapped to one of the `enum_types` are
+/// skipped, as no enum is generated for them.
+pub fn enum_defaults<'a>(
+    schemas: impl IntoIterator<Item = &'a Schema>,
+    enum_types: &HashMap<String, String>,
+) -> HashMap<String, EnumDefault> {
+    let mut key_defaults = HashMap::<String, Vec<(&str, &str, &str)>>::new();
+
+    for schema in schemas {
+        for key in &schema.keys {
+            let Some(SchemaKeySignature::Enum(enum_id)) = key.signature() else {
+                continue;
+            };
+
+            if enum_types.contains_key(&enum_id) {
+                continue;
+            }
+
+            key_defaults.entry(enum_id).or_default().push((
+                &key.name,
+                &schema.id,
+                key.default.trim(),
+            ));
+        }
+    }
+
+    key_defaults
+        .into_iter()
+        .map(|(enum_id, key_defaults)| {
+            let default = shared_default(&type_name_from_id(&enum_id), &key_defaults);
+            (enum_id, default)
+        })
+        .collect()
+}
+
+/// Returns the default of the enum with the given name, given the (key name,
+/// schema id, default) of each key that uses it. A warning is emitted if the
+/// keys have different defaults, which is also noted in the documentation of
+/// the enum, as warnings are only shown on nightly.
+fn shared_default(enum_name: &str, key_defaults: &[(&str, &str, &str)]) -> EnumDefault {
+    let nick = default_nick(key_defaults[0].2);
+
+    if let Some(nick) = nick.filter(|nick| {
+        key_defaults
+            .iter()
+            .all(|(_, _, default)| default_nick(default) == Some(nick))
+    }) {
+        return EnumDefault::Nick(nick.to_string());
+    }
+
+    let key_defaults = key_defaults
+        .iter()
+        .map(|(key_name, schema_id, default)| {
+            format!("`{}` in `{}` = `{}`", key_name, schema_id, default)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    emit_call_site_warning!(
+        "`Default` is not implemented for `{}`, since the keys that use it have different defaults: {}",
+        enum_name,
+        key_defaults
+    );
+
+    EnumDefault::Conflicting(format!(
+        "This doesn't implement `Default`, since the keys that use it have different defaults: {}.",
+        key_defaults
+    ))
+}
+
+/// Implements [`FromVariant`](gio::glib::variant::FromVariant), [`ToVariant`](gio::glib::variant::ToVariant),
+/// and [`StaticVariantType`](gio::glib::variant::StaticVariantType) for an enum with the given
+/// (nick, variant ident) tuple, where the variants are stored as their nick.
+pub fn variant_enum_impls(ident: &Ident, variants: &[(&str, &Ident)]) -> proc_macro2::TokenStream {
+    let from_variant_arms = variants.iter().map(|(nick, variant_ident)| {
+        quote! {
+            #nick => Some(Self::#variant_ident)
+        }
+    });
+
+    let to_variant_arms = variants.iter().map(|(nick, variant_ident)| {
+        quote! {
+            Self::#variant_ident => gio::glib::variant::ToVariant::to_variant(#nick)
+        }
+    });
+
+    quote! {
+        impl gio::glib::variant::StaticVariantType for #ident {
+            fn static_variant_type() -> std::borrow::Cow<'static, gio::glib::VariantTy> {
+                std::borrow::Cow::Borrowed(gio::glib::VariantTy::STRING)
+            }
+        }
+
+        impl gio::glib::variant::FromVariant for #ident {
+            fn from_variant(variant: &gio::glib::Variant) -> Option<Self> {
+                match variant.get::<String>()?.as_str() {
+                    #(#from_variant_arms),*,
+                    _ => None,
+                }
+            }
+        }
+
+        impl gio::glib::variant::ToVariant for #ident {
+            fn to_variant(&self) -> gio::glib::Variant {
+                match self {
+                    #(#to_variant_arms),*
+                }
+            }
+        }
+
+        impl std::convert::From<#ident> for gio::glib::Variant {
+            fn from(this: #ident) -> gio::glib::Variant {
+                gio::glib::variant::ToVariant::to_variant(&this)
+            }
+        }
+    }
+}
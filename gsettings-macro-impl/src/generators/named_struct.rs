@@ -0,0 +1,139 @@
+use heck::{ToPascalCase, ToSnakeCase};
+use proc_macro2::Span;
+use proc_macro_error::abort_call_site;
+use quote::quote;
+use syn::Ident;
+
+use super::{
+    parse_type, variant_type, Context, KeyGenerator, NamedStruct, SchemaKey, SchemaKeySignature,
+};
+
+pub fn key_generator<'a>(
+    key: &'a SchemaKey,
+    named_struct: &NamedStruct,
+    aux_visibility: syn::Visibility,
+) -> KeyGenerator<'a> {
+    let signature = match key.signature() {
+        Some(SchemaKeySignature::Type(type_)) => type_,
+        _ => abort_call_site!(
+            "named structs are only supported on keys with `type`; found key `{}`",
+            key.name
+        ),
+    };
+    let field_types = variant_type::tuple_fields(&signature).unwrap_or_else(|| {
+        abort_call_site!(
+            "named structs are only supported on keys with tuple type; found key `{}` with `{}` type",
+            key.name,
+            signature
+        )
+    });
+
+    let field_idents = match named_struct.fields {
+        Some(ref fields) => fields.clone(),
+        None => summary_fields(key),
+    };
+
+    if field_idents.len() != field_types.len() {
+        abort_call_site!(
+            "expected {} field names for key `{}`, found {}",
+            field_types.len(),
+            key.name,
+            field_idents.len()
+        );
+    }
+
+    let ident = named_struct
+        .name
+        .clone()
+        .unwrap_or_else(|| crate::parse_ident(&key.name.to_pascal_case(), Span::call_site()));
+    let name = ident.to_string();
+
+    let field_types = field_types
+        .iter()
+        .map(|field_type| parse_type(field_type))
+        .collect::<Vec<_>>();
+    let field_indices = 0..field_idents.len();
+
+    let docs = format!("The value of the `{}` key.", key.name);
+
+    let struct_token_stream = quote! {
+        #[doc = #docs]
+        #[derive(Clone, Debug, PartialEq)]
+        #aux_visibility struct #ident {
+            #(pub #field_idents: #field_types),*
+        }
+
+        impl gio::glib::variant::StaticVariantType for #ident {
+            fn static_variant_type() -> std::borrow::Cow<'static, gio::glib::VariantTy> {
+                std::borrow::Cow::Borrowed(gio::glib::VariantTy::new(#signature).unwrap())
+            }
+        }
+
+        impl gio::glib::variant::FromVariant for #ident {
+            fn from_variant(variant: &gio::glib::Variant) -> Option<Self> {
+                if !variant.is_type(&<Self as gio::glib::variant::StaticVariantType>::static_variant_type()) {
+                    return None;
+                }
+
+                Some(Self {
+                    #(#field_idents: variant.try_child_value(#field_indices)?.get()?),*
+                })
+            }
+        }
+
+        impl gio::glib::variant::ToVariant for #ident {
+            fn to_variant(&self) -> gio::glib::Variant {
+                gio::glib::Variant::tuple_from_iter([
+                    #(gio::glib::variant::ToVariant::to_variant(&self.#field_idents)),*
+                ])
+            }
+        }
+
+        impl std::convert::From<#ident> for gio::glib::Variant {
+            fn from(this: #ident) -> gio::glib::Variant {
+                gio::glib::variant::ToVariant::to_variant(&this)
+            }
+        }
+    };
+
+    KeyGenerator::new(key, Context::new_with_aux(&name, struct_token_stream))
+}
+
+/// Returns the field names listed in the key's summary, like `(width, height)`.
+fn summary_fields(key: &SchemaKey) -> Vec<Ident> {
+    let summary = key
+        .summary
+        .as_deref()
+        .and_then(|summary| summary.trim().strip_prefix('('))
+        .and_then(|summary| summary.strip_suffix(')'))
+        .unwrap_or_else(|| {
+            abort_call_site!(
+                "expected the summary of key `{}` to list the field names, like `(width, height)`; consider specifying `fields`",
+                key.name
+            )
+        });
+
+    let field_names = summary
+        .split(',')
+        .map(|field_name| field_name.to_snake_case())
+        .collect::<Vec<_>>();
+
+    // The summary is free-form text, so it may not yield proper field names
+    let field_idents = field_names
+        .iter()
+        .map(|field_name| syn::parse_str::<Ident>(field_name).ok())
+        .collect::<Option<Vec<_>>>()
+        .filter(|field_idents| {
+            field_idents
+                .iter()
+                .enumerate()
+                .all(|(i, ident)| !field_idents[..i].contains(ident))
+        });
+    field_idents.unwrap_or_else(|| {
+        abort_call_site!(
+            "the summary of key `{}` does not list valid and unique field names, found `{}`; consider specifying `fields`",
+            key.name,
+            field_names.join(", ")
+        )
+    })
+}
@@ -1,18 +1,25 @@
 use heck::ToPascalCase;
 
-use super::{Context, KeyGenerator, SchemaKey};
+use super::{Context, EnumDefault, KeyGenerator, SchemaKey};
 
-pub fn key_generator(key: &SchemaKey, aux_visibility: syn::Visibility) -> KeyGenerator<'_> {
+pub fn key_generator<'a>(
+    key: &'a SchemaKey,
+    aux_visibility: syn::Visibility,
+    choice_default: Option<&EnumDefault>,
+) -> KeyGenerator<'a> {
     if let Some(ref choices) = key.choices {
         let choice_enum_name = key.name.to_pascal_case();
         let choice_enum_token_stream = super::new_variant_enum(
             &choice_enum_name,
+            None,
             &choices
                 .choices
                 .iter()
                 .map(|choice| (choice.value.as_str(), None))
                 .collect::<Vec<_>>(),
             aux_visibility,
+            None,
+            choice_default,
         );
         KeyGenerator::new(
             key,
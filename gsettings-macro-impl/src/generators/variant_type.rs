@@ -0,0 +1,227 @@
+use quote::quote;
+
+use std::{iter::Peekable, str::Chars};
+
+use super::{Context, KeyGenerator, SchemaKey};
+
+/// The maximum number of tuple fields that glib implements the variant traits for.
+const MAX_TUPLE_FIELDS: usize = 16;
+
+/// Type signatures of the basic types, which are the only ones allowed as dictionary keys.
+const BASIC_TYPES: &str = "bynqiuxthdsog";
+
+pub fn key_generator<'a>(key: &'a SchemaKey, signature: &str) -> Option<KeyGenerator<'a>> {
+    Some(KeyGenerator::new(key, context(signature)?))
+}
+
+pub fn context(signature: &str) -> Option<Context> {
+    let rust_type = RustType::parse(signature)?;
+
+    let context = if let Some((key_type, value_type)) = rust_type.dict_entry {
+        // Accept any iterable of entries, like maps, arrays, or iterators, where
+        // the references need a named lifetime as they are in `impl Trait`
+        let lifetime = syn::Lifetime::new("'a", proc_macro2::Span::call_site());
+        let mut key_type = syn::parse_str::<syn::Type>(&key_type).ok()?;
+        let mut value_type = syn::parse_str::<syn::Type>(&value_type).ok()?;
+        set_reference_lifetimes(&mut key_type, &lifetime);
+        set_reference_lifetimes(&mut value_type, &lifetime);
+        let arg_type = quote! { impl IntoIterator<Item = (#key_type, #value_type)> }.to_string();
+        let map_type = syn::parse_str::<syn::Type>(&rust_type.arg_type).ok()?;
+        Context::new_with_conversion(
+            &arg_type,
+            &rust_type.ret_type,
+            quote! {
+                let value = gio::glib::variant::ToVariant::to_variant(
+                    &std::iter::Iterator::collect::<#map_type>(std::iter::IntoIterator::into_iter(value))
+                );
+            },
+        )
+        .with_set_generics(quote! { <'a> })
+    } else if signature == "v" || signature.starts_with('m') {
+        // `Into<Variant>` passes variants as is, while `ToVariant` boxes them,
+        // including the ones in maybe types
+        Context::new_with_conversion(
+            &rust_type.arg_type,
+            &rust_type.ret_type,
+            quote! {
+                let value = gio::glib::variant::ToVariant::to_variant(&value);
+            },
+        )
+    } else {
+        Context::new_dissimilar(&rust_type.arg_type, &rust_type.ret_type)
+    };
+
+    Some(context)
+}
+
+/// Gives the references in the given type that don't have a lifetime yet
+/// the given one.
+fn set_reference_lifetimes(ty: &mut syn::Type, lifetime: &syn::Lifetime) {
+    match ty {
+        syn::Type::Reference(reference) => {
+            reference.lifetime.get_or_insert_with(|| lifetime.clone());
+            set_reference_lifetimes(&mut reference.elem, lifetime);
+        }
+        syn::Type::Slice(slice) => set_reference_lifetimes(&mut slice.elem, lifetime),
+        syn::Type::Array(array) => set_reference_lifetimes(&mut array.elem, lifetime),
+        syn::Type::Paren(paren) => set_reference_lifetimes(&mut paren.elem, lifetime),
+        syn::Type::Group(group) => set_reference_lifetimes(&mut group.elem, lifetime),
+        syn::Type::Tuple(tuple) => {
+            for elem in &mut tuple.elems {
+                set_reference_lifetimes(elem, lifetime);
+            }
+        }
+        syn::Type::Path(path) => {
+            for segment in &mut path.path.segments {
+                if let syn::PathArguments::AngleBracketed(ref mut args) = segment.arguments {
+                    for arg in &mut args.args {
+                        if let syn::GenericArgument::Type(ref mut ty) = arg {
+                            set_reference_lifetimes(ty, lifetime);
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the return types of the fields of a tuple type signature.
+pub fn tuple_fields(signature: &str) -> Option<Vec<String>> {
+    let mut chars = signature.strip_prefix('(')?.chars().peekable();
+
+    let mut fields = Vec::new();
+    while chars.next_if_eq(&')').is_none() {
+        fields.push(RustType::parse_next(&mut chars)?.ret_type);
+    }
+
+    chars.next().is_none().then_some(fields)
+}
+
+/// The Rust types mapped from a GVariant type signature, which is borrowed
+/// when used as a parameter and owned when used as a return type.
+struct RustType {
+    arg_type: String,
+    ret_type: String,
+    /// The parameter types of the key and value, if it is a dictionary
+    dict_entry: Option<(String, String)>,
+}
+
+impl RustType {
+    fn new(type_: &str) -> Self {
+        Self::new_dissimilar(type_.to_string(), type_.to_string())
+    }
+
+    fn new_dissimilar(arg_type: String, ret_type: String) -> Self {
+        Self {
+            arg_type,
+            ret_type,
+            dict_entry: None,
+        }
+    }
+
+    /// Parses a complete type signature, returning `None` if it is invalid
+    /// or has no known mapping.
+    fn parse(signature: &str) -> Option<Self> {
+        let mut chars = signature.chars().peekable();
+        let rust_type = Self::parse_next(&mut chars)?;
+        chars.next().is_none().then_some(rust_type)
+    }
+
+    fn parse_next(chars: &mut Peekable<Chars<'_>>) -> Option<Self> {
+        let rust_type = match chars.next()? {
+            'b' => Self::new("bool"),
+            'y' => Self::new("u8"),
+            'n' => Self::new("i16"),
+            'q' => Self::new("u16"),
+            'i' => Self::new("i32"),
+            'u' => Self::new("u32"),
+            'x' => Self::new("i64"),
+            't' => Self::new("u64"),
+            'h' => Self::new("gio::glib::variant::Handle"),
+            'd' => Self::new("f64"),
+            's' => Self::new_dissimilar("&str".to_string(), "String".to_string()),
+            'o' => Self::new_dissimilar(
+                "&gio::glib::variant::ObjectPath".to_string(),
+                "gio::glib::variant::ObjectPath".to_string(),
+            ),
+            'g' => Self::new_dissimilar(
+                "&gio::glib::variant::Signature".to_string(),
+                "gio::glib::variant::Signature".to_string(),
+            ),
+            'v' => Self::new_dissimilar(
+                "&gio::glib::Variant".to_string(),
+                "gio::glib::Variant".to_string(),
+            ),
+            'm' => {
+                let inner = Self::parse_next(chars)?;
+                Self::new_dissimilar(
+                    format!("Option<{}>", inner.arg_type),
+                    format!("Option<{}>", inner.ret_type),
+                )
+            }
+            'a' if chars.next_if_eq(&'{').is_some() => {
+                // `f64` does not implement `Hash`, so it can't be a `HashMap` key
+                if !chars
+                    .peek()
+                    .is_some_and(|c| BASIC_TYPES.contains(*c) && *c != 'd')
+                {
+                    return None;
+                }
+
+                let key = Self::parse_next(chars)?;
+                let value = Self::parse_next(chars)?;
+                chars.next_if_eq(&'}')?;
+
+                Self {
+                    arg_type: format!(
+                        "std::collections::HashMap<{}, {}>",
+                        key.arg_type, value.arg_type
+                    ),
+                    ret_type: format!(
+                        "std::collections::HashMap<{}, {}>",
+                        key.ret_type, value.ret_type
+                    ),
+                    dict_entry: Some((key.arg_type, value.arg_type)),
+                }
+            }
+            'a' => {
+                let element = Self::parse_next(chars)?;
+                Self::new_dissimilar(
+                    format!("&[{}]", element.arg_type),
+                    format!("Vec<{}>", element.ret_type),
+                )
+            }
+            '(' => {
+                let mut fields = Vec::new();
+                while chars.next_if_eq(&')').is_none() {
+                    fields.push(Self::parse_next(chars)?);
+                }
+
+                if fields.len() > MAX_TUPLE_FIELDS {
+                    return None;
+                }
+
+                Self::new_dissimilar(
+                    tuple(fields.iter().map(|field| field.arg_type.as_str())),
+                    tuple(fields.iter().map(|field| field.ret_type.as_str())),
+                )
+            }
+            _ => return None,
+        };
+
+        Some(rust_type)
+    }
+}
+
+fn tuple<'a>(fields: impl ExactSizeIterator<Item = &'a str>) -> String {
+    // Single element tuples need a trailing comma
+    let is_single = fields.len() == 1;
+    let joined = fields.collect::<Vec<_>>().join(", ");
+
+    if is_single {
+        format!("({},)", joined)
+    } else {
+        format!("({})", joined)
+    }
+}
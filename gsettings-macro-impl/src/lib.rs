@@ -0,0 +1,2029 @@
+#![warn(rust_2018_idioms)]
+#![deny(rustdoc::broken_intra_doc_links)]
+//! Procedural macros of [`gsettings-macro`](https://docs.rs/gsettings-macro).
+//!
+//! This crate is an implementation detail; depend on `gsettings-macro` instead.
+
+mod derive;
+mod generators;
+mod schema;
+mod source;
+
+use deluxe::SpannedValue;
+use heck::{ToPascalCase, ToSnakeCase};
+use proc_macro2::Span;
+use proc_macro_error::{
+    abort, abort_call_site, emit_call_site_error, emit_error, emit_warning, proc_macro_error,
+};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Token,
+};
+
+use std::{
+    collections::HashMap,
+    fs, iter,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    generators::{
+        BytestringType, GetResult, KeyGenerators, NamedStruct, OverrideType, SerdeFormat,
+        ValueConversion,
+    },
+    schema::{KeySignature as SchemaKeySignature, Schema, SchemaList},
+    source::{GenSettings, SchemaIdAttr, Substitution},
+};
+
+// TODO:
+// * Replace proc-macro-error dep with syn::Result
+// * Use `quote_spanned` where applicable for better error propagation on generated code
+// * Remove serde and deluxe dependencies (consider using quick-xml directly or xmlserde)
+// * Add `bind_#key writable`, `user_#key_value`, `connect_#key_writable_changed` variants
+// * Add trybuild tests
+
+#[derive(deluxe::ParseMetaItem)]
+struct GenSettingsTypes {
+    file: Option<SpannedValue<String>>,
+    xml: Option<SpannedValue<String>>,
+    glib_types: deluxe::Flag,
+}
+
+enum SchemaSource {
+    File(String),
+    Inline(String),
+}
+
+impl SchemaSource {
+    fn from_attrs(
+        file_attr: Option<SpannedValue<String>>,
+        xml_attr: Option<SpannedValue<String>>,
+    ) -> (Span, Self) {
+        match (file_attr, xml_attr) {
+            (Some(file_attr), None) => (
+                file_attr.span(),
+                Self::File(SpannedValue::into_inner(file_attr)),
+            ),
+            (None, Some(xml_attr)) => (
+                xml_attr.span(),
+                Self::Inline(SpannedValue::into_inner(xml_attr)),
+            ),
+            (Some(_), Some(_)) => abort_call_site!("cannot specify both `file` and `xml`"),
+            (None, None) => abort_call_site!("must specify either `file` or `xml`"),
+        }
+    }
+}
+
+impl SchemaIdAttr {
+    /// Returns the tokens of the id, which check that a const id is the
+    /// id of the schema the struct is generated for when evaluated.
+    fn checked_token_stream(&self, schema_id: &str) -> proc_macro2::TokenStream {
+        match self {
+            Self::Literal(_) | Self::Env(_) => self.to_token_stream(),
+            Self::Const(path) => {
+                let message = format!(
+                    "the id `{{}}` does not match the schema `{}` that the settings are generated for; consider specifying `id` with a known value",
+                    schema_id
+                );
+                quote! {
+                    {
+                        let schema_id: &str = #path;
+                        assert_eq!(schema_id, #schema_id, #message, schema_id);
+                        schema_id
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ToTokens for SchemaIdAttr {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(match self {
+            Self::Literal(lit) => quote! { #lit },
+            Self::Env(name) => quote! { env!(#name) },
+            Self::Const(path) => quote! { #path },
+        });
+    }
+}
+
+#[derive(deluxe::ParseAttributes)]
+struct GenSettingsSchema {
+    id: SpannedValue<String>,
+    name: Option<SpannedValue<String>>,
+}
+
+#[derive(deluxe::ParseAttributes)]
+struct GenSettingsDefine {
+    signature: Option<SpannedValue<String>>,
+    key_name: Option<SpannedValue<String>>,
+    arg_type: SpannedValue<String>,
+    ret_type: SpannedValue<String>,
+    get_with: Option<SpannedValue<String>>,
+    set_with: Option<SpannedValue<String>>,
+    fallible: deluxe::Flag,
+    serde: Option<SpannedValue<String>>,
+    adapter: Option<SpannedValue<String>>,
+}
+
+#[derive(deluxe::ParseAttributes)]
+struct GenSettingsStruct {
+    signature: Option<SpannedValue<String>>,
+    key_name: Option<SpannedValue<String>>,
+    name: Option<SpannedValue<String>>,
+    fields: Option<SpannedValue<String>>,
+}
+
+#[derive(deluxe::ParseAttributes)]
+struct GenSettingsEnum {
+    id: SpannedValue<String>,
+    r#type: String,
+}
+
+#[derive(deluxe::ParseAttributes)]
+struct GenSettingsSkip {
+    signature: Option<SpannedValue<String>>,
+    key_name: Option<SpannedValue<String>>,
+}
+
+struct SettingsStruct {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    struct_token: Token![struct],
+    ident: syn::Ident,
+    semi_token: Token![;],
+}
+
+impl Parse for SettingsStruct {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        Ok(Self {
+            attrs: input.call(syn::Attribute::parse_outer)?,
+            vis: input.parse()?,
+            struct_token: input.parse()?,
+            ident: input.parse()?,
+            semi_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for SettingsStruct {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.vis.to_tokens(tokens);
+        self.struct_token.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+
+        let field: syn::FieldsUnnamed = syn::parse_quote!((gio::Settings));
+        field.to_tokens(tokens);
+
+        self.semi_token.to_tokens(tokens);
+    }
+}
+
+/// Macro for typesafe [`gio::Settings`] key access.
+///
+/// The macro's main purpose is to reduce the risk of mistyping a key,
+/// using the wrong method to access values, inputting incorrect values,
+/// and to reduce boilerplate. Additionally, the summary, description,
+/// and default value are included in the documentation of each generated
+/// method. This would be beneficial if you use tools like
+/// [`rust-analyzer`](https://rust-analyzer.github.io/).
+///
+/// **⚠️ IMPORTANT ⚠️**
+///
+/// Both `gio` and `glib` need to be in scope, so unless they are direct crate
+/// dependencies, you need to import them because `gen_settings` is using
+/// them internally. For example:
+///
+/// ```ignore
+/// use gtk::{gio, glib};
+/// ```
+///
+/// The generated code also passes values between them and `gsettings-macro`,
+/// which is built against gtk-rs 0.22, so `gio` and `glib` must be from the
+/// same release (`gio` 0.22 and `glib` 0.22). Otherwise,
+/// compilation fails with an error stating that `gio::glib` is not the glib
+/// used by `gsettings-macro`.
+///
+/// ### Example
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// pub struct ApplicationSettings;
+///
+/// let settings = ApplicationSettings::new("io.github.seadve.test");
+///
+/// // `i` D-Bus type
+/// settings.set_window_width(100);
+/// assert_eq!(settings.window_width(), 100);
+///
+/// // enums
+/// settings.set_alert_sound(AlertSound::Glass);
+/// assert_eq!(settings.alert_sound(), AlertSound::Glass);
+///
+/// // bitflags
+/// settings.set_space_style(SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA);
+/// assert_eq!(
+///     settings.space_style(),
+///     SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA
+/// );
+/// ```
+///
+/// Note: A relative file path is resolved against the directory where the
+/// `Cargo.toml` file is located (i.e., `CARGO_MANIFEST_DIR`), and then
+/// against the current directory. It can also be explicitly prefixed with
+/// `$CARGO_MANIFEST_DIR` or `$OUT_DIR`, the latter being useful for schema
+/// files generated by build scripts. The schema file is tracked by the
+/// compiler, so the code is regenerated when it is modified.
+///
+/// ```ignore
+/// #[gen_settings(file = "$OUT_DIR/io.github.seadve.test.gschema.xml")]
+/// pub struct ApplicationSettings;
+/// ```
+///
+/// ### Generated methods
+///
+/// The procedural macro generates the following [`gio::Settings`] methods
+/// for each key in the schema:
+///
+/// * `set` -> `set_${key}`, which panics when writing in a readonly
+/// key, and `try_set_${key}`, which behaves the same as the original method.
+/// * `get` -> `${key}`, which panics when the stored value can't be converted,
+/// and `try_${key}`, which returns the error instead.
+/// * `connect_changed` -> `connect_${key}_changed`
+/// * `bind` -> `bind_${key}`
+/// * `create_action` -> `create_${key}_action`
+/// * `default_value` -> `${key}_default_value`
+/// * `reset` -> `reset_${key}`
+///
+/// ### Known D-Bus type signatures
+///
+/// The setter and getter methods has the following parameter and
+/// return type, depending on the key's type signature.
+///
+/// | Type Signature | Parameter Type | Return Type   |
+/// | -------------- | -------------- | ------------- |
+/// | b              | `bool`         | `bool`        |
+/// | y              | `u8`           | `u8`          |
+/// | n              | `i16`          | `i16`         |
+/// | q              | `u16`          | `u16`         |
+/// | i              | `i32`          | `i32`         |
+/// | u              | `u32`          | `u32`         |
+/// | x              | `i64`          | `i64`         |
+/// | t              | `u64`          | `u64`         |
+/// | h              | `glib::variant::Handle` | `glib::variant::Handle` |
+/// | d              | `f64`          | `f64`         |
+/// | s *            | `&str`         | `String`      |
+/// | o              | `&glib::variant::ObjectPath` | `glib::variant::ObjectPath` |
+/// | g              | `&glib::variant::Signature` | `glib::variant::Signature` |
+/// | v              | `&glib::Variant` | `glib::Variant` |
+/// | (..)           | `(T, ..)`      | `(T, ..)`     |
+/// | a..            | `&[T]`         | `Vec<T>`      |
+/// | ay **          | `&Path`        | `PathBuf`     |
+/// | a{..}          | `impl IntoIterator<Item = (K, V)>` | `HashMap<K, V>` |
+/// | m..            | `Option<T>`    | `Option<T>`   |
+///
+/// \* If the key of type signature `s` has no `choice` attribute
+/// specified in the GSchema, the parameter and return types stated
+/// in the table would be applied. Otherwise, it will generate an
+/// enum, like described in the next section, and use it as the parameter
+/// and return types, instead of `&str` and `String` respectively.
+///
+/// \** Keys of type signature `ay` are treated as bytestrings, which have
+/// a trailing nul byte, like the ones written by `gsettings set`. The types
+/// can be changed for all of them with the `bytestring` attribute: `path`
+/// (default) for `&Path` and `PathBuf`, `os_string` for `&OsStr` and `OsString`,
+/// or `bytes` for `&[u8]` and `Vec<u8>`, which appends the trailing nul byte
+/// on set and strips it on get. For binary data, which may end with a nul byte,
+/// `raw_bytes` also uses `&[u8]` and `Vec<u8>` but stores and returns the
+/// value as is.
+///
+/// ```ignore
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml", bytestring = "bytes")]
+/// pub struct Settings;
+/// ```
+///
+/// Tuples, arrays, and maybe types can be nested and map their fields or elements with
+/// the same table, using the parameter types for the parameter and the
+/// return types for the return type. For example, `a(si)` has `&[(&str, i32)]`
+/// parameter type and `Vec<(String, i32)>` return type.
+///
+/// Dictionaries accept any iterable of entries, like maps or arrays of
+/// tuples, so `a{si}` can be set with `[("name", 100)]` and is returned
+/// as `HashMap<String, i32>`. When nested, they have `HashMap<K, V>`
+/// parameter type. To get a `BTreeMap` instead, use `#[gen_settings_define]`
+/// with `BTreeMap<K, V>` return type.
+///
+/// It will not compile if the type signature is not defined above.
+/// However, it is possible to explicitly skip generating methods
+/// for a specific key or type signature using the attribute
+/// `#[gen_settings_skip]`, or define a custom parameter and return
+/// types using `#[gen_settings_define]` attribute. The usage of
+/// the latter will be further explained in the following sections.
+///
+/// ### Enums and Flags
+///
+/// The macro will also automatically generate enums or flags. A single
+/// type is generated for each `<enum>` or `<flags>` definition, and it is
+/// shared by all the keys that refer to it. It is named after the last
+/// component of the definition's id in pascal case (e.g., `AlertSound` for
+/// `io.github.seadve.AlertSound`), with the full id as a documentation alias.
+///
+/// If it is an enum, it would generated a normal Rust enum with each nick
+/// specified in the GSchema converted to pascal case as an enum variant.
+/// The enum would implement both [`ToVariant`] and [`FromVariant`], [`Clone`],
+/// [`Copy`], [`Debug`], [`Hash`], [`PartialEq`], [`Eq`], [`PartialOrd`], and
+/// [`Ord`], where the variants are ordered by their values, along with:
+///
+/// * `ALL` and `iter()`, which list the variants in the order of the schema.
+/// * `nick()` and `from_nick()`, which convert between the variants and
+/// their nicks, and [`Display`] and [`FromStr`], which do the same.
+/// * [`TryFrom<i32>`], which uses the values specified in the schema.
+/// * [`Default`], which returns the default value of the keys that use
+/// the enum in all the schemas of the file, including their `<override>`s,
+/// if they all have the same one, so that it is the same as with
+/// [`gen_settings_types!`]. Otherwise, it is not implemented, which is noted
+/// in the documentation of the enum, and a warning is emitted, which is only
+/// shown on nightly.
+///
+/// The same applies to the enums generated for string keys with choices,
+/// except for [`TryFrom<i32>`], and that [`Default`] considers the keys with
+/// the same name in the generated schemas.
+///
+/// On the other hand, if it is a flag, it would generate bitflags
+/// same as the bitflags generated by the [`bitflags`] macro with each
+/// nick specified in the GSchema converted to screaming snake case as
+/// a const flag.
+///
+/// The generated types, enum or bitflags, would have the same
+/// visibility and scope with the generated struct.
+///
+/// With `glib_types`, they are also registered as GLib enum and flags types,
+/// like [`glib::Enum`] and [`glib::flags`] do, with the nicks in the schema as
+/// the value nicks. They can then be used as types of GObject properties
+/// and in [`glib::Value`], so a key can be bound directly to a property of
+/// that type. The type is registered under the definition's id in pascal
+/// case (e.g., `IoGithubSeadveAlertSound`), so it has to be generated only
+/// once in the program, for example with [`gen_settings_types`](gen_settings_types!).
+///
+/// #### Panics
+///
+/// The types are registered when they are first used as GLib types, which
+/// panics if a type with the same name is already registered. This happens
+/// when the same definition is generated with `glib_types` more than once
+/// in the program, like with both `gen_settings` and `gen_settings_types!`.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml", glib_types)]
+/// pub struct Settings;
+///
+/// let settings = Settings::new("io.github.seadve.test");
+/// // `player` has an `alert-sound` property of type `AlertSound`
+/// settings.bind_alert_sound(&player, "alert-sound").build();
+/// ```
+///
+/// ### Existing enums
+///
+/// Instead of generating a type, an `<enum>` or `<flags>` definition can be
+/// mapped to an existing type with `#[gen_settings_enum]`, which is then used
+/// by all the keys that refer to the definition. Enums need to derive
+/// [`SettingsEnum`](macro@SettingsEnum), and flags types need to implement
+/// `SettingsFlags`, like the flags generated by [`gen_settings_types!`] do.
+/// Their nicks and values are checked against the definition at compile
+/// time.
+///
+/// ```ignore
+/// use gsettings_macro::{gen_settings, SettingsEnum};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, SettingsEnum)]
+/// pub enum Sound {
+///     Bark = 0,
+///     Glass = 2,
+///     #[nick = "drip"]
+///     WaterDrip = 1,
+/// }
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// #[gen_settings_enum(id = "io.github.seadve.AlertSound", type = "Sound")]
+/// pub struct Settings;
+///
+/// let settings = Settings::new("io.github.seadve.test");
+/// settings.set_alert_sound(Sound::WaterDrip);
+/// ```
+///
+/// ### Named structs
+///
+/// Keys with tuple type can be mapped to a generated struct with a field
+/// for each element of the tuple using `#[gen_settings_struct]`. The field
+/// names are specified with `fields`, or are taken from the key's summary
+/// when it lists them, like `(width, height)`, in snake case. Since the
+/// summary is free-form text, it fails to compile if it does not yield
+/// valid and unique field names, in which case `fields` has to be
+/// specified. The struct is named after
+/// the key in pascal case unless `name` is specified, and it implements
+/// [`ToVariant`] and [`FromVariant`], [`Clone`], [`Debug`], and
+/// [`PartialEq`].
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// #[gen_settings_struct(key_name = "dimensions", fields = "width, height")]
+/// // The summary of `last-file` is `(path, line, is modified)`
+/// #[gen_settings_struct(key_name = "last-file", name = "FileLocation")]
+/// pub struct Settings;
+///
+/// let settings = Settings::new("io.github.seadve.test");
+///
+/// settings.set_dimensions(Dimensions {
+///     width: 100,
+///     height: 200,
+/// });
+/// assert_eq!(settings.dimensions().width, 100);
+///
+/// assert!(!settings.last_file().is_modified);
+/// ```
+///
+/// Like the enums and flags, the structs have the same visibility and
+/// scope with the generated struct.
+///
+/// ### Skipping methods generation
+///
+/// This would be helpful if you want to have full control
+/// with the key without the macro intervening. For example:
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./tests/io.github.seadve.test.gschema.xml",
+///     id = "io.github.seadve.test"
+/// )]
+/// // Skip generating methods for keys with type signature `(ss)`
+/// #[gen_settings_skip(signature = "(ss)")]
+/// // Skip generating methods for the key of name `some-key-name`
+/// #[gen_settings_skip(key_name = "some-key-name")]
+/// pub struct Settings;
+///
+/// impl Settings {
+///     pub fn set_some_key_name(value: &std::path::Path) {
+///         ...
+///     }
+/// }
+/// ```
+///
+/// ### Defining custom types
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// use std::path::{Path, PathBuf};
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// // Define custom parameter and return types for keys with type `(ss)`
+/// #[gen_settings_define(
+///     signature = "(ss)",
+///     arg_type = "(&str, &str)",
+///     ret_type = "(String, String)"
+/// )]
+/// // Define custom parameter and return types for key with name `cache-dir`
+/// #[gen_settings_define(key_name = "cache-dir", arg_type = "&Path", ret_type = "PathBuf")]
+/// pub struct SomeAppSettings;
+///
+/// let settings = SomeAppSettings::new("io.github.seadve.test");
+///
+/// settings.set_cache_dir(Path::new("/some_dir"));
+/// assert_eq!(settings.cache_dir(), PathBuf::from("/some_dir"));
+///
+/// settings.set_string_tuple(("hi", "hi2"));
+/// assert_eq!(settings.string_tuple(), ("hi".into(), "hi2".into()));
+/// ```
+///
+/// The type specified in `arg_type` and `ret_type` has to be on scope or
+/// you can specify the full path.
+///
+/// If you somehow do not want an enum parameter and return types for `s`
+/// type signature with choices. You can also use this to override that behavior.
+///
+/// Note: The type has to implement [`SettingsValue`] or it would fail to
+/// compile, unless `get_with` and `set_with` are specified. It is already
+/// implemented for types that implement [`StaticVariantType`], [`ToVariant`],
+/// and [`FromVariant`], so only newtypes and other types without those need
+/// to implement it.
+///
+/// ```ignore
+/// use gsettings_macro::{gen_settings, SettingsValue};
+/// use gio::glib::{self, Variant, VariantTy};
+///
+/// use std::borrow::Cow;
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct Percent(u8);
+///
+/// impl SettingsValue for Percent {
+///     fn variant_type() -> Cow<'static, VariantTy> {
+///         Cow::Borrowed(VariantTy::BYTE)
+///     }
+///
+///     fn from_variant(variant: &Variant) -> Result<Self, glib::BoolError> {
+///         match variant.get::<u8>() {
+///             Some(value) if value <= 100 => Ok(Self(value)),
+///             _ => Err(glib::bool_error!("invalid percent `{}`", variant)),
+///         }
+///     }
+///
+///     fn to_variant(&self) -> Result<Variant, glib::BoolError> {
+///         Ok(Variant::from(self.0))
+///     }
+/// }
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// #[gen_settings_define(key_name = "opacity", arg_type = "Percent", ret_type = "Percent")]
+/// pub struct SomeAppSettings;
+/// ```
+///
+/// Note: the generated code uses [`SettingsValue`] through the
+/// `gsettings-macro` crate, which is found even if it is renamed in
+/// `Cargo.toml`.
+///
+/// ### Converting values
+///
+/// For types that can't implement [`ToVariant`] and [`FromVariant`], like
+/// foreign types, functions that convert from and to the key's own type
+/// can be specified with `get_with` and `set_with`. The key's own type is
+/// the return type stated in the table above (e.g., `u32` for `u`).
+///
+/// With `fallible`, the functions return a [`Result`], where the error
+/// implements [`Display`](std::fmt::Display). The error is then returned
+/// by `try_set_${key}` and the additional `try_${key}` getter, while
+/// `set_${key}`, `${key}`, and `${key}_default_value` panic on error.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// use std::time::Duration;
+///
+/// fn duration_from_secs(secs: u32) -> Duration {
+///     Duration::from_secs(secs as u64)
+/// }
+///
+/// fn duration_to_secs(duration: Duration) -> u32 {
+///     duration.as_secs() as u32
+/// }
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// #[gen_settings_define(
+///     key_name = "timeout",
+///     arg_type = "Duration",
+///     ret_type = "Duration",
+///     get_with = "duration_from_secs",
+///     set_with = "duration_to_secs"
+/// )]
+/// // `parse_url` returns `Result<Url, url::ParseError>`, and `try_url_to_string`
+/// // returns `Result<String, E>`
+/// #[gen_settings_define(
+///     key_name = "homepage",
+///     arg_type = "&Url",
+///     ret_type = "Url",
+///     get_with = "parse_url",
+///     set_with = "try_url_to_string",
+///     fallible
+/// )]
+/// pub struct Settings;
+///
+/// let settings = Settings::new("io.github.seadve.test");
+///
+/// settings.set_timeout(Duration::from_secs(60));
+/// assert_eq!(settings.timeout(), Duration::from_secs(60));
+///
+/// match settings.try_homepage() {
+///     Ok(url) => println!("{}", url),
+///     Err(err) => eprintln!("invalid homepage: {}", err),
+/// }
+/// ```
+///
+/// Common conversions are shipped in `gsettings_macro::adapters`, and can be
+/// used by name with `adapter`, like `seconds` for `u` or `t` to
+/// [`Duration`](std::time::Duration), `date_time` and `system_time` for `x`,
+/// `from_str` and `uri` for `s`, and `path` for `ay`. They are fallible, so
+/// `try_${key}` is also generated for them.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// use std::time::SystemTime;
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// #[gen_settings_define(
+///     key_name = "last-opened",
+///     arg_type = "SystemTime",
+///     ret_type = "SystemTime",
+///     adapter = "system_time"
+/// )]
+/// #[gen_settings_define(
+///     key_name = "accent-color",
+///     arg_type = "&gdk::RGBA",
+///     ret_type = "gdk::RGBA",
+///     adapter = "from_str"
+/// )]
+/// pub struct Settings;
+/// ```
+///
+/// ### Serde
+///
+/// With the `serde` cargo feature, types that implement serde's `Serialize`
+/// and `Deserialize` can be stored with `serde`. With `serde = "json"`, the
+/// value is stored as a JSON string in a key with `s` type signature. With
+/// `serde = "variant"`, it is stored as a value of the key's type, where
+/// tuple structs are stored as tuples and structs with named fields as
+/// dictionaries keyed by the field names. Structs with named fields can't be
+/// stored as tuples, since the order of their fields is not preserved.
+///
+/// Errors are returned by `try_set_${key}` and `try_${key}`, while
+/// `set_${key}`, `${key}`, and `${key}_default_value` panic on error.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// pub struct ExportPreset {
+///     name: String,
+///     quality: u8,
+/// }
+///
+/// // Stored as `(sxb)`
+/// #[derive(Serialize, Deserialize)]
+/// pub struct RecentFile(String, i64, bool);
+///
+/// #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+/// #[gen_settings_define(
+///     key_name = "export-preset",
+///     arg_type = "&ExportPreset",
+///     ret_type = "ExportPreset",
+///     serde = "json"
+/// )]
+/// #[gen_settings_define(
+///     key_name = "recent-files",
+///     arg_type = "&[RecentFile]",
+///     ret_type = "Vec<RecentFile>",
+///     serde = "variant"
+/// )]
+/// pub struct Settings;
+/// ```
+///
+/// ### Default trait
+///
+/// The schema id can be specified as an attribute, making it implement
+/// [`Default`] and create a `new` constructor without parameters.
+/// Otherwise, it will not implement [`Default`] and would require the
+/// schema id as an parameter in the the constructor or the `new` method.
+///
+/// The following is an example of defining the `id` attribute in the macro:
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./tests/io.github.seadve.test.gschema.xml",
+///     id = "io.github.seadve.test"
+/// )]
+/// pub struct ApplicationSettings;
+///
+/// // The id is specified above so it is not needed
+/// // to specify it in the constructor.
+/// let settings = ApplicationSettings::new();
+/// let another_instance = ApplicationSettings::default();
+/// ```
+///
+/// The id can also be taken from an environment variable at compile time
+/// using `env!("..")`, or from a path to a `&'static str` const, which is
+/// useful if it differs between builds (e.g., development and release builds).
+/// An id from an environment variable is checked against the schema file
+/// at compile time, so the variable must be defined when compiling. The
+/// value of a const is not known to the macro, so the last schema in the
+/// file is used, and `new` panics if the const is not its id.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// // Using an environment variable, e.g., set by the build script
+/// #[gen_settings(file = "./data/io.github.seadve.Mousai.gschema.xml", id = env!("APP_ID"))]
+/// pub struct Settings;
+///
+/// // Using a const, e.g., from a generated config module
+/// #[gen_settings(file = "./data/io.github.seadve.Mousai.gschema.xml", id = crate::config::APP_ID)]
+/// pub struct OtherSettings;
+/// ```
+///
+/// ### Multiple schemas
+///
+/// If the schema file has multiple schemas, the `id` attribute selects
+/// the schema used for the annotated struct. Structs for the other schemas
+/// can be generated from the same invocation using `#[gen_settings_schema]`,
+/// or all of them at once using the `all_schemas` flag. Their names default
+/// to the last component of their id in pascal case with a `Settings` suffix.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./tests/io.github.seadve.multi.gschema.xml",
+///     id = "io.github.seadve.multi"
+/// )]
+/// // Generate `WindowState` for the schema `io.github.seadve.multi.window`
+/// #[gen_settings_schema(id = "io.github.seadve.multi.window", name = "WindowState")]
+/// // Generate `PluginSettings` for the schema `io.github.seadve.multi.plugin`
+/// #[gen_settings_schema(id = "io.github.seadve.multi.plugin")]
+/// pub struct Settings;
+///
+/// let settings = Settings::new();
+/// let window_state = WindowState::new();
+/// let plugin_settings = PluginSettings::new();
+/// ```
+///
+/// The generated structs have the same visibility as the annotated struct.
+/// Overrides defined with `#[gen_settings_define]` and `#[gen_settings_skip]`
+/// apply to all of them, and enums and flags shared between the schemas
+/// are only generated once.
+///
+/// ### Child schemas
+///
+/// For each `<child>` element in the schema, a method named after the child
+/// is generated, which returns the settings for the child schema using
+/// [`gio::Settings::child`], so the path and backend carry over. If the
+/// struct for the child schema is generated in the same invocation, the
+/// method returns it. Otherwise, it returns an untyped [`gio::Settings`].
+/// A child whose method name is already taken by another method, like the
+/// ones of a key, is an error.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./tests/io.github.seadve.multi.gschema.xml",
+///     id = "io.github.seadve.multi"
+/// )]
+/// #[gen_settings_schema(id = "io.github.seadve.multi.window", name = "WindowState")]
+/// pub struct Settings;
+///
+/// let settings = Settings::new();
+///
+/// // `<child name="window" schema="io.github.seadve.multi.window"/>`
+/// let window_state: WindowState = settings.window();
+/// window_state.set_width(100);
+///
+/// // `<child name="plugin" schema="io.github.seadve.multi.plugin"/>`
+/// let plugin_settings: gio::Settings = settings.plugin();
+/// ```
+///
+/// ### Schema inheritance
+///
+/// Schemas that extend another schema include every inherited key and
+/// child, with the defaults replaced by their `<override>` elements, which
+/// must refer to inherited keys. If the
+/// extended schema is also generated, a `${struct}Keys` trait with its
+/// key methods is generated and implemented by it and by all the structs
+/// of the schemas extending it, so code written against the extended
+/// schema can accept any of them.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./tests/io.github.seadve.multi.gschema.xml",
+///     id = "io.github.seadve.multi.default-profile"
+/// )]
+/// #[gen_settings_schema(id = "io.github.seadve.multi.profile", name = "Profile")]
+/// pub struct DefaultProfile;
+///
+/// fn increase_font_size(profile: &impl ProfileKeys) {
+///     profile.set_font_size(profile.font_size() + 1);
+/// }
+///
+/// let default_profile = DefaultProfile::new();
+/// increase_font_size(&default_profile);
+///
+/// // Overridden by `<override name="title">"Default"</override>`
+/// assert_eq!(default_profile.title_default_value(), "Default");
+/// ```
+///
+/// ### Relocatable schemas
+///
+/// Schemas without a `path` attribute are relocatable, so instead of `new`
+/// and the [`Default`] implementation, the struct would have a `new_with_path`
+/// constructor that takes the path where the settings are stored. A `path_for_id`
+/// helper is also generated to build paths for multiple instances of the schema
+/// below a base path, like a list of profiles. On the other hand, schemas with
+/// a fixed path would have a `PATH` constant.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./tests/io.github.seadve.multi.gschema.xml",
+///     id = "io.github.seadve.multi.profile"
+/// )]
+/// pub struct Profile;
+///
+/// let path = Profile::path_for_id("/io/github/seadve/multi/profiles/", "some-uuid");
+/// assert_eq!(path, "/io/github/seadve/multi/profiles/some-uuid/");
+///
+/// let profile = Profile::new_with_path(&path);
+/// ```
+///
+/// ### Inline schema
+///
+/// Instead of a separate file, the schema XML can also be written directly
+/// in the macro using the `xml` attribute. The struct would then have a
+/// `SCHEMA_XML` constant and a `write_schema_file` function, which writes
+/// the XML to a `${schema_id}.gschema.xml` file in the given directory,
+/// so it can be compiled and installed. This way, the Rust source is the
+/// single source of truth.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     xml = r#"
+///         <schemalist>
+///             <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+///                 <key name="window-width" type="i">
+///                     <default>600</default>
+///                 </key>
+///             </schema>
+///         </schemalist>
+///     "#,
+///     id = "io.github.seadve.test"
+/// )]
+/// pub struct Settings;
+///
+/// // For example, in an installation script or an `xtask`
+/// Settings::write_schema_file("data/").unwrap();
+/// ```
+///
+/// Since build scripts can't call into the crate they build, the `build`
+/// feature also provides `gsettings_macro::build::write_schema_files`, which
+/// extracts the inline XML from a source file and writes it out the same way.
+///
+/// ```ignore
+/// // build.rs
+/// fn main() {
+///     let out_dir = std::env::var("OUT_DIR").unwrap();
+///     gsettings_macro::build::write_schema_files("src/settings.rs", out_dir).unwrap();
+/// }
+/// ```
+///
+/// ### Schema templates
+///
+/// Templates like `.gschema.xml.in` files, which are usually configured
+/// by the build system, can also be used by substituting their `@placeholder@`s
+/// with `#[gen_settings_substitute]`. The value can be specified either directly
+/// or from an environment variable at compile time, like the ones set by the
+/// build system or by a build script using `cargo:rustc-env`. In templates,
+/// which are the `.in` files and the schemas with `#[gen_settings_substitute]`,
+/// any `@placeholder@` left without a `#[gen_settings_substitute]` is an error.
+///
+/// ```ignore
+/// use gsettings_macro::gen_settings;
+///
+/// #[gen_settings(
+///     file = "./data/io.github.seadve.Mousai.gschema.xml.in",
+///     id = "io.github.seadve.Mousai.Devel"
+/// )]
+/// // Replace `@APP_ID@` with `io.github.seadve.Mousai.Devel`
+/// #[gen_settings_substitute(placeholder = "APP_ID", value = "io.github.seadve.Mousai.Devel")]
+/// // Replace `@gettext-package@` with the value of `GETTEXT_PACKAGE` environment variable
+/// #[gen_settings_substitute(placeholder = "gettext-package", env = "GETTEXT_PACKAGE")]
+/// pub struct Settings;
+/// ```
+///
+/// [`gio::Settings`]: https://docs.rs/gio/latest/gio/struct.Settings.html
+/// [`gio::Settings::child`]: https://docs.rs/gio/latest/gio/prelude/trait.SettingsExt.html#tymethod.child
+/// [`SettingsValue`]: https://docs.rs/gsettings-macro/latest/gsettings_macro/trait.SettingsValue.html
+/// [`StaticVariantType`]: https://docs.rs/glib/latest/glib/variant/trait.StaticVariantType.html
+/// [`ToVariant`]: https://docs.rs/glib/latest/glib/variant/trait.ToVariant.html
+/// [`FromVariant`]: https://docs.rs/glib/latest/glib/variant/trait.FromVariant.html
+/// [`bitflags`]: https://docs.rs/bitflags/latest/bitflags/macro.bitflags.html
+/// [`Display`]: std::fmt::Display
+/// [`FromStr`]: std::str::FromStr
+/// [`glib::Enum`]: https://docs.rs/glib/latest/glib/derive.Enum.html
+/// [`glib::flags`]: https://docs.rs/glib/latest/glib/attr.flags.html
+/// [`glib::Value`]: https://docs.rs/glib/latest/glib/value/struct.Value.html
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn gen_settings(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let GenSettings {
+        file: file_attr,
+        xml: xml_attr,
+        id: id_attr,
+        all_schemas,
+        bytestring: bytestring_attr,
+        glib_types,
+    } = match deluxe::parse2(attr.into()) {
+        Ok(gen_settings) => gen_settings,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let bytestring_type = bytestring_attr.map(|bytestring_attr| {
+        BytestringType::from_name(&bytestring_attr).unwrap_or_else(|| {
+            abort!(
+                bytestring_attr.span(),
+                "expected one of `path`, `os_string`, `bytes` or `raw_bytes`"
+            )
+        })
+    });
+    let (schema_source_span, schema_source) = SchemaSource::from_attrs(file_attr, xml_attr);
+
+    let settings_struct = syn::parse_macro_input!(item as SettingsStruct);
+
+    // Parse attributes
+    let mut schema_attrs = Vec::new();
+    let mut override_attrs = Vec::new();
+    let mut substitutions = Vec::new();
+    let mut enum_attrs = Vec::new();
+    for attr in &settings_struct.attrs {
+        if attr.path().is_ident("gen_settings_define") {
+            let GenSettingsDefine {
+                signature,
+                key_name,
+                arg_type,
+                ret_type,
+                get_with,
+                set_with,
+                fallible,
+                serde,
+                adapter,
+            } = match deluxe::parse_attributes::<_, GenSettingsDefine>(attr) {
+                Ok(gen_settings) => gen_settings,
+                Err(err) => {
+                    emit_error!(attr.span(), err);
+                    continue;
+                }
+            };
+
+            let serde = match serde {
+                Some(serde) => match SerdeFormat::from_name(&serde) {
+                    Some(_) if get_with.is_some() || set_with.is_some() || adapter.is_some() => {
+                        emit_error!(
+                            attr.span(),
+                            "cannot specify both `serde` and `get_with`, `set_with`, or `adapter`"
+                        );
+                        continue;
+                    }
+                    Some(serde) => Some(serde),
+                    None => {
+                        emit_error!(serde.span(), "expected one of `json` or `variant`");
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            let conversion = match (get_with, set_with, adapter) {
+                (None, None, Some(adapter)) => {
+                    if fallible.is_set() {
+                        emit_error!(attr.span(), "cannot specify both `adapter` and `fallible`");
+                    }
+
+                    if *adapter == "uri" && !cfg!(feature = "uri") {
+                        emit_error!(
+                            adapter.span(),
+                            "the `uri` adapter requires the `uri` feature of `gsettings-macro`"
+                        );
+                        continue;
+                    }
+
+                    if !ADAPTERS.contains(&adapter.as_str()) {
+                        emit_error!(
+                            adapter.span(),
+                            "expected one of {}",
+                            ADAPTERS
+                                .iter()
+                                .map(|adapter| format!("`{}`", adapter))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        );
+                        continue;
+                    }
+
+                    let module = format_ident!("{}", *adapter);
+                    let crate_path = crate_path();
+                    Some(ValueConversion {
+                        get_with: syn::parse_quote!(#crate_path::adapters::#module::get),
+                        set_with: syn::parse_quote!(#crate_path::adapters::#module::set),
+                        is_fallible: true,
+                    })
+                }
+                (_, _, Some(_)) => {
+                    emit_error!(
+                        attr.span(),
+                        "cannot specify both `adapter` and `get_with` or `set_with`"
+                    );
+                    continue;
+                }
+                (Some(get_with), Some(set_with), None) => {
+                    match (parse_func_path(get_with), parse_func_path(set_with)) {
+                        (Some(get_with), Some(set_with)) => Some(ValueConversion {
+                            get_with,
+                            set_with,
+                            is_fallible: fallible.is_set(),
+                        }),
+                        _ => continue,
+                    }
+                }
+                (None, None, None) => {
+                    if fallible.is_set() {
+                        emit_error!(attr.span(), "`fallible` requires `get_with` and `set_with`");
+                    }
+                    None
+                }
+                _ => {
+                    emit_error!(attr.span(), "must specify both `get_with` and `set_with`");
+                    continue;
+                }
+            };
+
+            override_attrs.push((
+                attr,
+                signature,
+                key_name,
+                OverrideType::Define {
+                    arg_type: SpannedValue::into_inner(arg_type),
+                    ret_type: SpannedValue::into_inner(ret_type),
+                    conversion,
+                    serde,
+                },
+            ));
+        } else if attr.path().is_ident("gen_settings_struct") {
+            let GenSettingsStruct {
+                signature,
+                key_name,
+                name,
+                fields,
+            } = match deluxe::parse_attributes::<_, GenSettingsStruct>(attr) {
+                Ok(gen_settings_struct) => gen_settings_struct,
+                Err(err) => {
+                    emit_error!(attr.span(), err);
+                    continue;
+                }
+            };
+
+            let name = name.map(|name| parse_ident(&name, name.span()));
+            let fields = fields.map(|fields| {
+                let field_idents = fields
+                    .split(',')
+                    .map(|field| parse_ident(field.trim(), fields.span()))
+                    .collect::<Vec<_>>();
+                if let Some(field_ident) = field_idents
+                    .iter()
+                    .enumerate()
+                    .find_map(|(i, ident)| field_idents[..i].contains(ident).then_some(ident))
+                {
+                    abort!(fields.span(), "duplicate field name `{}`", field_ident);
+                }
+                field_idents
+            });
+
+            override_attrs.push((
+                attr,
+                signature,
+                key_name,
+                OverrideType::Struct(NamedStruct { name, fields }),
+            ));
+        } else if attr.path().is_ident("gen_settings_skip") {
+            let GenSettingsSkip {
+                signature,
+                key_name,
+            } = match deluxe::parse_attributes::<_, GenSettingsSkip>(attr) {
+                Ok(gen_settings) => gen_settings,
+                Err(err) => {
+                    emit_error!(attr.span(), err);
+                    continue;
+                }
+            };
+
+            override_attrs.push((attr, signature, key_name, OverrideType::Skip));
+        } else if attr.path().is_ident("gen_settings_enum") {
+            match deluxe::parse_attributes::<_, GenSettingsEnum>(attr) {
+                Ok(gen_settings_enum) => enum_attrs.push(gen_settings_enum),
+                Err(err) => emit_error!(attr.span(), err),
+            }
+        } else if attr.path().is_ident("gen_settings_schema") {
+            match deluxe::parse_attributes::<_, GenSettingsSchema>(attr) {
+                Ok(gen_settings_schema) => schema_attrs.push(gen_settings_schema),
+                Err(err) => emit_error!(attr.span(), err),
+            }
+        } else if attr.path().is_ident("gen_settings_substitute") {
+            match Substitution::from_attr(attr) {
+                Ok(substitution) => substitutions.push(substitution),
+                Err(err) => emit_error!(err.span(), err),
+            }
+        } else {
+            emit_error!(
+                attr.span(),
+                "expected `#[gen_settings_define( .. )]`, `#[gen_settings_struct( .. )]`, `#[gen_settings_enum( .. )]`, `#[gen_settings_skip( .. )]`, `#[gen_settings_schema( .. )]` or `#[gen_settings_substitute( .. )]`"
+            );
+        }
+    }
+
+    // Parse schema list
+    let is_inline = matches!(schema_source, SchemaSource::Inline(_));
+    let (schema_list, schema_file_contents, tracked_schema_file_path) =
+        parse_schema_list(schema_source, schema_source_span, &substitutions);
+
+    // Get main schema and its id
+    let known_id = id_attr.as_ref().and_then(|id_attr| {
+        let id = id_attr
+            .value()
+            .unwrap_or_else(|err| abort!(err.span(), err));
+        Some((id?, id_attr.span()))
+    });
+    let schema = if let Some((schema_id, id_attr_span)) = known_id {
+        schema_list
+            .schemas
+            .iter()
+            .find(|schema| schema.id == schema_id)
+            .unwrap_or_else(|| {
+                abort!(
+                    id_attr_span,
+                    "id does not match any schema specified in the schema file"
+                )
+            })
+    } else {
+        if schema_list.schemas.len() > 1 {
+            emit_warning!(
+                schema_source_span,
+                "schema file has multiple schemas, using the last one; consider specifying `id` with a known value"
+            );
+        }
+
+        schema_list
+            .schemas
+            .last()
+            .unwrap_or_else(|| abort!(schema_source_span, "schema file must have a single schema"))
+    };
+    let schema_id = id_attr.map(|id_attr| id_attr.checked_token_stream(&schema.id));
+
+    // Get other schemas to generate
+    let mut other_schemas: Vec<(syn::Ident, &Schema)> = Vec::new();
+    for GenSettingsSchema { id, name } in schema_attrs {
+        let id_span = id.span();
+        let id = SpannedValue::into_inner(id);
+
+        let Some(other_schema) = schema_list.schemas.iter().find(|other| other.id == id) else {
+            emit_error!(
+                id_span,
+                "id does not match any schema specified in the schema file"
+            );
+            continue;
+        };
+
+        if other_schema.id == schema.id
+            || other_schemas
+                .iter()
+                .any(|(_, other)| other.id == other_schema.id)
+        {
+            emit_error!(id_span, "duplicate schema");
+            continue;
+        }
+
+        let ident = if let Some(name) = name {
+            parse_ident(&name, name.span())
+        } else {
+            parse_ident(&default_struct_name(&other_schema.id), id_span)
+        };
+
+        other_schemas.push((ident, other_schema));
+    }
+    if all_schemas.is_set() {
+        for other_schema in &schema_list.schemas {
+            if other_schema.id == schema.id
+                || other_schemas
+                    .iter()
+                    .any(|(_, other)| other.id == other_schema.id)
+            {
+                continue;
+            }
+
+            let ident = parse_ident(&default_struct_name(&other_schema.id), schema_source_span);
+            other_schemas.push((ident, other_schema));
+        }
+    }
+
+    // Parse overrides
+    let all_keys = iter::once(schema)
+        .chain(other_schemas.iter().map(|(_, other_schema)| *other_schema))
+        .flat_map(|schema| &schema.keys)
+        .collect::<Vec<_>>();
+    let known_signatures = all_keys
+        .iter()
+        .map(|key| {
+            key.signature().unwrap_or_else(|| {
+                abort!(schema_source_span, "expected one of `type`, `enum` or `flags` specified attribute on key `{}` in the schema", key.name);
+            })
+        })
+        .collect::<Vec<_>>();
+    let known_key_names = all_keys
+        .iter()
+        .map(|key| key.name.as_str())
+        .collect::<Vec<_>>();
+    let mut signature_overrides = HashMap::new();
+    let mut key_name_overrides = HashMap::new();
+    for (attr, signature, key_name, override_type) in override_attrs {
+        match (signature, key_name) {
+            (Some(_), Some(_)) => {
+                emit_error!(
+                    attr.span(),
+                    "cannot specify both `signature` and `key_name`"
+                )
+            }
+            (None, None) => {
+                emit_error!(attr.span(), "must specify either `signature` or `key_name`")
+            }
+            (Some(signature), None) => {
+                let signature_span = signature.span();
+                let signature_str = SpannedValue::into_inner(signature);
+                let signature_type = SchemaKeySignature::Type(signature_str);
+
+                if !known_signatures.contains(&signature_type) {
+                    emit_error!(signature_span, "useless define for this signature");
+                }
+
+                if signature_overrides.contains_key(&signature_type) {
+                    emit_error!(signature_span, "duplicate override");
+                }
+
+                signature_overrides.insert(signature_type, override_type);
+            }
+            (None, Some(key_name)) => {
+                let key_name_span = key_name.span();
+                let key_name_str = SpannedValue::into_inner(key_name);
+
+                if !known_key_names.contains(&key_name_str.as_str()) {
+                    emit_error!(key_name_span, "key_name not found in the schema");
+                }
+
+                if key_name_overrides.contains_key(&key_name_str) {
+                    emit_error!(key_name_span, "duplicate override");
+                }
+
+                key_name_overrides.insert(key_name_str, override_type);
+            }
+        }
+    }
+
+    // Parse enum types
+    let mut enum_types = HashMap::new();
+    for GenSettingsEnum { id, r#type } in enum_attrs {
+        let id_span = id.span();
+        let id = SpannedValue::into_inner(id);
+
+        let is_known = schema_list.enums.iter().any(|enum_| enum_.id == id)
+            || schema_list.flags.iter().any(|flag| flag.id == id);
+        if !is_known {
+            emit_error!(
+                id_span,
+                "id does not match any enum or flags specified in the schema file"
+            );
+            continue;
+        }
+
+        if enum_types.contains_key(&id) {
+            emit_error!(id_span, "duplicate enum type");
+            continue;
+        }
+
+        enum_types.insert(id, r#type);
+    }
+
+    // Generate keys
+    let enums = schema_list
+        .enums
+        .iter()
+        .map(|enum_| (enum_.id.to_string(), enum_))
+        .collect::<HashMap<_, _>>();
+    let flags = schema_list
+        .flags
+        .iter()
+        .map(|flag| (flag.id.to_string(), flag))
+        .collect::<HashMap<_, _>>();
+    let mut key_generators = KeyGenerators::with_defaults(enums, flags);
+    key_generators.add_signature_overrides(signature_overrides);
+    key_generators.add_key_name_overrides(key_name_overrides);
+    key_generators.set_choice_defaults(
+        iter::once(schema).chain(other_schemas.iter().map(|(_, other_schema)| *other_schema)),
+    );
+    key_generators.set_enum_defaults(generators::enum_defaults(&schema_list.schemas, &enum_types));
+    key_generators.add_enum_types(enum_types);
+    key_generators.set_glib_types(glib_types.is_set());
+    if let Some(bytestring_type) = bytestring_type {
+        key_generators.set_bytestring_type(bytestring_type);
+    }
+
+    // Generate code
+    let struct_idents = iter::once((schema.id.as_str(), &settings_struct.ident))
+        .chain(
+            other_schemas
+                .iter()
+                .map(|(ident, other_schema)| (other_schema.id.as_str(), ident)),
+        )
+        .collect::<HashMap<_, _>>();
+    let mut generator = SettingsGenerator {
+        schema_list: &schema_list,
+        struct_idents,
+        key_generators: &key_generators,
+        aux_visibility: &settings_struct.vis,
+        aux: Auxiliaries::default(),
+    };
+
+    let mut expanded =
+        generator.generate(&settings_struct, &settings_struct.ident, schema, schema_id);
+
+    if is_inline {
+        let struct_ident = &settings_struct.ident;
+        let schema_file_name = format!("{}.gschema.xml", schema.id);
+
+        expanded.extend(quote! {
+            impl #struct_ident {
+                /// The schema XML specified in the macro.
+                pub const SCHEMA_XML: &str = #schema_file_contents;
+
+                /// Writes [`Self::SCHEMA_XML`] to a `.gschema.xml` file named after
+                /// the schema id in the given directory, so it can be compiled
+                /// and installed, and returns the path of the written file.
+                pub fn write_schema_file(dir: impl AsRef<std::path::Path>) -> std::io::Result<std::path::PathBuf> {
+                    let path = dir.as_ref().join(#schema_file_name);
+                    std::fs::write(&path, Self::SCHEMA_XML)?;
+                    Ok(path)
+                }
+            }
+        });
+    }
+
+    for (ident, other_schema) in &other_schemas {
+        let vis = &settings_struct.vis;
+        let struct_token_stream = quote! {
+            #vis struct #ident(gio::Settings);
+        };
+
+        expanded.extend(generator.generate(
+            &struct_token_stream,
+            ident,
+            other_schema,
+            Some(other_schema.id.to_token_stream()),
+        ));
+    }
+
+    let aux_token_stream = generator.aux.token_stream;
+    let tracked_schema_file_path = tracked_schema_file_path.iter();
+    let substitution_env_names = substitutions
+        .iter()
+        .filter_map(|substitution| substitution.env_name.as_ref());
+    let crate_path = crate_path();
+
+    // `include_bytes!` and `env!` make the compiler track the schema file and
+    // the environment variables used in substitutions, so the macro is
+    // re-expanded when they change
+    quote! {
+        #(const _: &[u8] = include_bytes!(#tracked_schema_file_path);)*
+        #(const _: &str = env!(#substitution_env_names);)*
+        const _: () = #crate_path::__private::assert_same_glib::<gio::glib::Variant>();
+
+        #aux_token_stream
+
+        #expanded
+    }
+    .into()
+}
+
+/// Macro for generating only the enums and flags of a schema file, without
+/// a settings struct.
+///
+/// This is useful for crates that need the types of a schema, for example in
+/// shared widgets, but don't own a [`gio::Settings`]. It accepts the same
+/// `file` or `xml` argument as [`gen_settings`], and generates a public type
+/// for each `<enum>` and `<flags>` definition in the schema file, which
+/// are the same as the ones described in [Enums and Flags](gen_settings#enums-and-flags),
+/// including their registration as GLib types with `glib_types`.
+///
+/// ```ignore
+/// mod types {
+///     gsettings_macro::gen_settings_types!(file = "./tests/io.github.seadve.test.gschema.xml");
+/// }
+///
+/// use gio::prelude::*;
+/// use types::AlertSound;
+///
+/// let variant = AlertSound::Glass.to_variant();
+/// assert_eq!(variant.get::<AlertSound>(), Some(AlertSound::Glass));
+/// ```
+///
+/// [`gio::Settings`]: https://docs.rs/gio/latest/gio/struct.Settings.html
+#[proc_macro]
+#[proc_macro_error]
+pub fn gen_settings_types(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let GenSettingsTypes {
+        file: file_attr,
+        xml: xml_attr,
+        glib_types,
+    } = match deluxe::parse2(input.into()) {
+        Ok(gen_settings_types) => gen_settings_types,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let (schema_source_span, schema_source) = SchemaSource::from_attrs(file_attr, xml_attr);
+
+    let (schema_list, _, tracked_schema_file_path) =
+        parse_schema_list(schema_source, schema_source_span, &[]);
+
+    let types_token_stream =
+        generators::types_token_stream(&schema_list, syn::parse_quote!(pub), glib_types.is_set());
+    let tracked_schema_file_path = tracked_schema_file_path.iter();
+
+    quote! {
+        #(const _: &[u8] = include_bytes!(#tracked_schema_file_path);)*
+
+        #types_token_stream
+    }
+    .into()
+}
+
+/// Derive macro for generating a schema from a struct, which is the
+/// reverse of [`gen_settings`], so the Rust types and the schema XML
+/// don't drift apart.
+///
+/// Each field of the struct becomes a key, named after the field in kebab case,
+/// with the key's type signature inferred from the field type. The struct
+/// would then have a `SCHEMA_ID` constant, a `schema_xml` function, which
+/// returns the schema XML, and a `write_schema_file` function, which writes
+/// it to a `${schema_id}.gschema.xml` file in the given directory. Additionally,
+/// a `${Struct}Settings` struct is generated with the same methods as the
+/// ones generated by [`gen_settings`], and the struct can be read from and
+/// written to it with `load` and `store`.
+///
+/// The following attributes are supported:
+///
+/// * On the struct: `id`, `path`, and `name`, which overrides the name of
+/// the generated settings struct.
+/// * On the fields: `default`, in GVariant text format, `summary`,
+/// `description`, `min` and `max`, which specify the range, `choices = [".."]`,
+/// `name`, which overrides the key name, and `signature`, which overrides
+/// the inferred type signature.
+///
+/// | Field Type    | Type Signature |
+/// | ------------- | -------------- |
+/// | `bool`        | `b`            |
+/// | `u8`          | `y`            |
+/// | `i16`         | `n`            |
+/// | `u16`         | `q`            |
+/// | `i32`         | `i`            |
+/// | `u32`         | `u`            |
+/// | `i64`         | `x`            |
+/// | `u64`         | `t`            |
+/// | `f64`         | `d`            |
+/// | `String`      | `s`            |
+/// | `Vec<String>` | `as`           |
+/// | `PathBuf`     | `ay`           |
+///
+/// Other field types without a `signature` are expected to be enums that
+/// also derive `GSettingsSchema` and implement [`Clone`], which become
+/// `<enum>` definitions in the schema, or fail to compile with an error
+/// pointing at the field type. The nick of each variant defaults to its name in kebab case,
+/// and can be overridden with `nick`, and its value, which must fit in an `i32`,
+/// follows the discriminant.
+///
+/// ```ignore
+/// use gsettings_macro::GSettingsSchema;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, GSettingsSchema)]
+/// #[gsettings(id = "io.github.seadve.test.AlertSound")]
+/// pub enum AlertSound {
+///     Bark,
+///     #[gsettings(nick = "drip")]
+///     WaterDrip,
+///     Glass,
+/// }
+///
+/// #[derive(GSettingsSchema)]
+/// #[gsettings(id = "io.github.seadve.test", path = "/io/github/seadve/test/")]
+/// pub struct Preferences {
+///     #[gsettings(default = "600", summary = "Window width", min = "100")]
+///     window_width: i32,
+///     #[gsettings(default = "'light'", choices = ["light", "dark"])]
+///     theme: String,
+///     #[gsettings(default = "'bark'")]
+///     alert_sound: AlertSound,
+/// }
+///
+/// // For example, in an installation script or an `xtask`
+/// Preferences::write_schema_file("data/").unwrap();
+///
+/// let settings = PreferencesSettings::new();
+/// settings.set_alert_sound(AlertSound::Glass);
+/// assert_eq!(settings.alert_sound(), AlertSound::Glass);
+///
+/// let mut preferences = Preferences::load(&settings).unwrap();
+/// preferences.window_width = 800;
+/// preferences.store(&settings).unwrap();
+/// assert_eq!(settings.window_width(), 800);
+/// ```
+#[proc_macro_derive(GSettingsSchema, attributes(gsettings))]
+#[proc_macro_error]
+pub fn gsettings_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive::derive(input).into()
+}
+
+/// Derive macro for using an existing enum in place of a generated one,
+/// with `#[gen_settings_enum]` in [`gen_settings`].
+///
+/// It implements [`ToVariant`], [`FromVariant`], and [`StaticVariantType`],
+/// where each variant is stored as its nick, and the [`SettingsEnum`] trait,
+/// which lists the nick and value of each variant. The nick defaults to the
+/// variant name in kebab case, and can be overridden with `#[nick = ".."]`,
+/// while the value is the discriminant of the variant.
+///
+/// ```ignore
+/// use gsettings_macro::SettingsEnum;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, SettingsEnum)]
+/// pub enum AlertSound {
+///     Bark = 0,
+///     Glass = 2,
+///     #[nick = "drip"]
+///     WaterDrip = 1,
+/// }
+/// ```
+///
+/// [`SettingsEnum`]: https://docs.rs/gsettings-macro/latest/gsettings_macro/trait.SettingsEnum.html
+/// [`StaticVariantType`]: https://docs.rs/glib/latest/glib/variant/trait.StaticVariantType.html
+/// [`ToVariant`]: https://docs.rs/glib/latest/glib/variant/trait.ToVariant.html
+/// [`FromVariant`]: https://docs.rs/glib/latest/glib/variant/trait.FromVariant.html
+#[proc_macro_derive(SettingsEnum, attributes(nick))]
+#[proc_macro_error]
+pub fn settings_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive::derive_settings_enum(input).into()
+}
+
+/// Auxiliary items (e.g., enums and flags) generated for the keys, which
+/// are only emitted once even if they are shared between multiple keys
+/// or schemas.
+#[derive(Default)]
+struct Auxiliaries {
+    /// Name to the definition of the type, to detect types of different
+    /// definitions with the same name
+    definitions: HashMap<String, String>,
+    token_stream: proc_macro2::TokenStream,
+}
+
+impl Auxiliaries {
+    fn insert(&mut self, name: &str, token_stream: proc_macro2::TokenStream) {
+        let definition = token_stream.to_string();
+
+        match self.definitions.get(name) {
+            Some(other_definition) if *other_definition != definition => {
+                emit_call_site_error!(
+                    "conflicting definitions for the generated type `{}`; consider overriding the keys that use one of them with `#[gen_settings_define( .. )]`",
+                    name
+                );
+            }
+            Some(_) => {}
+            None => {
+                self.definitions.insert(name.to_string(), definition);
+                self.token_stream.extend(token_stream);
+            }
+        }
+    }
+}
+
+/// Reads and parses the schema list with its `@placeholder@`s substituted,
+/// which must all be substituted if it is a template, returning the substituted XML and the path of the file to track, if any.
+fn parse_schema_list(
+    schema_source: SchemaSource,
+    schema_source_span: Span,
+    substitutions: &[Substitution],
+) -> (SchemaList, String, Option<String>) {
+    let is_template = match &schema_source {
+        SchemaSource::File(path) => source::is_template(Some(path), substitutions),
+        SchemaSource::Inline(_) => source::is_template(None, substitutions),
+    };
+
+    let (schema_file_contents, tracked_schema_file_path) = match schema_source {
+        SchemaSource::File(schema_file_path) => {
+            let schema_file_path =
+                resolve_schema_file_path(&schema_file_path).unwrap_or_else(|err| {
+                    abort!(schema_source_span, "failed to find schema file: {}", err)
+                });
+            let schema_file_contents =
+                fs::read_to_string(&schema_file_path).unwrap_or_else(|err| {
+                    abort!(schema_source_span, "failed to open schema file: {}", err);
+                });
+            // `include_bytes!` requires an absolute path as it is relative to the
+            // file where the macro is invoked
+            let tracked_schema_file_path = fs::canonicalize(&schema_file_path)
+                .ok()
+                .and_then(|path| path.to_str().map(|path| path.to_string()));
+            (schema_file_contents, tracked_schema_file_path)
+        }
+        SchemaSource::Inline(xml) => (xml, None),
+    };
+    let schema_file_contents =
+        source::substitute(&schema_file_contents, substitutions, is_template)
+            .unwrap_or_else(|err| abort!(schema_source_span, err));
+    let mut schema_list: SchemaList = quick_xml::de::from_str(&schema_file_contents)
+        .unwrap_or_else(|err| abort!(schema_source_span, "failed to parse schema file: {}", err));
+    schema_list
+        .resolve_extends()
+        .unwrap_or_else(|err| abort!(schema_source_span, "failed to resolve schema file: {}", err));
+
+    (schema_list, schema_file_contents, tracked_schema_file_path)
+}
+
+/// Resolves the schema file path, which is either absolute, prefixed with
+/// `$CARGO_MANIFEST_DIR` or `$OUT_DIR`, or relative to `CARGO_MANIFEST_DIR`
+/// or the current directory, in that order.
+fn resolve_schema_file_path(path: &str) -> Result<PathBuf, String> {
+    for env_name in ["CARGO_MANIFEST_DIR", "OUT_DIR"] {
+        if let Some(rest) = path.strip_prefix(&format!("${}", env_name)) {
+            let dir = std::env::var(env_name)
+                .map_err(|err| format!("failed to get `{}`: {}", env_name, err))?;
+            let resolved_path = PathBuf::from(format!("{}{}", dir, rest));
+
+            return if resolved_path.is_file() {
+                Ok(resolved_path)
+            } else {
+                Err(format!("`{}` does not exist", resolved_path.display()))
+            };
+        }
+    }
+
+    let path = Path::new(path);
+
+    let candidates = if path.is_absolute() {
+        vec![path.to_path_buf()]
+    } else {
+        [
+            std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from),
+            std::env::current_dir().ok(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(path))
+        .collect()
+    };
+
+    candidates
+        .iter()
+        .find(|candidate| candidate.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            let tried = candidates
+                .iter()
+                .map(|candidate| format!("`{}`", candidate.display()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("tried {}", tried)
+        })
+}
+
+/// Names of the modules in `gsettings_macro::adapters`, which can be used
+/// with `adapter`.
+const ADAPTERS: &[&str] = &[
+    "seconds",
+    "date_time",
+    "system_time",
+    "from_str",
+    #[cfg(feature = "uri")]
+    "uri",
+    "path",
+];
+
+/// Parses the path of a function used in `get_with` or `set_with`, emitting
+/// an error if it is invalid.
+fn parse_func_path(path: SpannedValue<String>) -> Option<syn::Path> {
+    let path_span = path.span();
+
+    syn::parse_str::<syn::Path>(&path)
+        .map_err(|err| emit_error!(path_span, "invalid function path: {}", err))
+        .ok()
+}
+
+/// Returns the path of the `gsettings-macro` crate used by the generated
+/// code, taking into account that it may be renamed in the user's
+/// `Cargo.toml`.
+pub(crate) fn crate_path() -> syn::Path {
+    let name = match proc_macro_crate::crate_name("gsettings-macro") {
+        Ok(proc_macro_crate::FoundCrate::Name(name)) => name,
+        // Within the crate itself, the macros are only used by its tests
+        // and doctests, which refer to it by its name
+        Ok(proc_macro_crate::FoundCrate::Itself) | Err(_) => "gsettings_macro".to_string(),
+    };
+    let ident = syn::Ident::new(&name, Span::call_site());
+    syn::parse_quote!(::#ident)
+}
+
+/// Parses the given name as an identifier, aborting at the given span if it
+/// is not a valid one.
+pub(crate) fn parse_ident(name: &str, span: Span) -> syn::Ident {
+    let mut ident = syn::parse_str::<syn::Ident>(name)
+        .unwrap_or_else(|_| abort!(span, "`{}` is not a valid identifier", name));
+    ident.set_span(span);
+    ident
+}
+
+/// Returns the struct name used for a schema with the given id,
+/// which is the last component of the id in pascal case with `Settings`
+/// suffix (e.g., `WindowStateSettings` for `io.github.seadve.test.window-state`).
+fn default_struct_name(schema_id: &str) -> String {
+    let last_component = schema_id.rsplit('.').next().unwrap_or(schema_id);
+    format!("{}Settings", last_component.to_pascal_case())
+}
+
+/// Generates the settings structs, sharing the auxiliary items between them.
+struct SettingsGenerator<'a> {
+    schema_list: &'a SchemaList,
+    /// Schema id to the ident of its generated struct
+    struct_idents: HashMap<&'a str, &'a syn::Ident>,
+    key_generators: &'a KeyGenerators<'a>,
+    aux_visibility: &'a syn::Visibility,
+    aux: Auxiliaries,
+}
+
+impl SettingsGenerator<'_> {
+    fn generate(
+        &mut self,
+        struct_def: &impl ToTokens,
+        struct_ident: &syn::Ident,
+        schema: &Schema,
+        schema_id: Option<proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
+        let mut keys_token_stream = proc_macro2::TokenStream::new();
+        // Names of the methods generated so far, to check the ones of the
+        // children against
+        let mut func_names = ["new", "new_with_path", "path_for_id", "write_schema_file"]
+            .into_iter()
+            .map(|func_name| (func_name.to_string(), "the settings struct".to_string()))
+            .collect::<HashMap<_, _>>();
+
+        for key in &schema.keys {
+            match self
+                .key_generators
+                .get(key, self.aux_visibility.clone())
+                .unwrap()
+            {
+                GetResult::Skip => (),
+                GetResult::Some(generator) => {
+                    keys_token_stream.extend(generator.to_token_stream());
+                    for func_name in generator.func_names() {
+                        func_names.insert(func_name, format!("key `{}`", key.name));
+                    }
+
+                    if let Some((aux_name, aux_token_stream)) = generator.auxiliary() {
+                        self.aux.insert(aux_name, aux_token_stream);
+                    }
+                }
+                GetResult::Unknown => {
+                    emit_call_site_error!(
+                        "unsupported {} signature used by key `{}`; consider using `#[gen_settings_define( .. )]` or skip it with `#[gen_settings_skip( .. )]`",
+                        &key.signature().unwrap(),
+                        &key.name,
+                    )
+                }
+            }
+        }
+
+        let mut children_token_stream = proc_macro2::TokenStream::new();
+
+        for child in &schema.children {
+            let child_name = child.name.as_str();
+            let func_name = child_name.to_snake_case();
+            if let Some(owner) = func_names.get(&func_name) {
+                emit_call_site_error!(
+                    "the accessor `{}` of `<child name=\"{}\">` in schema `{}` conflicts with a method of {}; consider renaming the child",
+                    func_name,
+                    child_name,
+                    schema.id,
+                    owner
+                );
+                continue;
+            }
+            func_names.insert(func_name.clone(), format!("child `{}`", child_name));
+            let func_ident = syn::Ident::new(&func_name, Span::call_site());
+            let func_docs = format!(
+                "Returns the child settings `{}` with `{}` schema.",
+                child_name, child.schema
+            );
+
+            // Fallback to untyped settings if the child schema is not generated
+            children_token_stream.extend(
+                if let Some(child_struct_ident) = self.struct_idents.get(child.schema.as_str()) {
+                    quote! {
+                        #[doc = #func_docs]
+                        pub fn #func_ident(&self) -> #child_struct_ident {
+                            #child_struct_ident(gio::prelude::SettingsExt::child(&self.0, #child_name))
+                        }
+                    }
+                } else {
+                    quote! {
+                        #[doc = #func_docs]
+                        pub fn #func_ident(&self) -> gio::Settings {
+                            gio::prelude::SettingsExt::child(&self.0, #child_name)
+                        }
+                    }
+                },
+            );
+        }
+
+        let constructor_token_stream = if let Some(ref path) = schema.path {
+            let new_token_stream = if let Some(ref schema_id) = schema_id {
+                quote! {
+                    pub fn new() -> Self {
+                        Self(gio::Settings::new(#schema_id))
+                    }
+                }
+            } else {
+                quote! {
+                    pub fn new(schema_id: &str) -> Self {
+                        Self(gio::Settings::new(schema_id))
+                    }
+                }
+            };
+
+            quote! {
+                /// The path of the schema.
+                pub const PATH: &str = #path;
+
+                #new_token_stream
+            }
+        } else {
+            // Relocatable schemas must be created with a path
+            let (schema_id_param, schema_id_arg) = if let Some(ref schema_id) = schema_id {
+                (None, quote! { #schema_id })
+            } else {
+                (Some(quote! { schema_id: &str, }), quote! { schema_id })
+            };
+
+            quote! {
+                /// Creates the settings for the relocatable schema at the given path.
+                ///
+                /// # Panics
+                ///
+                /// Panics if the path does not start and end with a slash, or contains
+                /// two adjacent slashes.
+                pub fn new_with_path(#schema_id_param path: &str) -> Self {
+                    assert!(
+                        path.starts_with('/') && path.ends_with('/') && !path.contains("//"),
+                        "invalid path `{}`; must start and end with a slash and must not contain two adjacent slashes",
+                        path
+                    );
+
+                    Self(gio::Settings::with_path(#schema_id_arg, path))
+                }
+
+                /// Returns the path for the given id below the base path (i.e., `${base_path}${id}/`),
+                /// which can be used to store multiple instances of the schema, like a list of profiles.
+                ///
+                /// # Panics
+                ///
+                /// Panics if the base path does not end with a slash, or the id is empty
+                /// or contains a slash.
+                pub fn path_for_id(base_path: &str, id: &str) -> String {
+                    assert!(base_path.ends_with('/'), "invalid base path `{}`; must end with a slash", base_path);
+                    assert!(!id.is_empty() && !id.contains('/'), "invalid id `{}`; must not be empty or contain a slash", id);
+
+                    format!("{}{}/", base_path, id)
+                }
+            }
+        };
+
+        let mut expanded = quote! {
+            #[derive(Clone, Hash, PartialEq, Eq, gio::glib::ValueDelegate)]
+            #[value_delegate(nullable)]
+            #struct_def
+
+            impl #struct_ident {
+                #constructor_token_stream
+
+                #keys_token_stream
+
+                #children_token_stream
+            }
+
+            impl std::ops::Deref for #struct_ident {
+                type Target = gio::Settings;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl std::ops::DerefMut for #struct_ident {
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    &mut self.0
+                }
+            }
+
+            impl std::fmt::Debug for #struct_ident {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    std::fmt::Debug::fmt(&self.0, f)
+                }
+            }
+        };
+
+        if schema_id.is_some() && schema.path.is_some() {
+            expanded.extend(quote! {
+                impl Default for #struct_ident {
+                    fn default() -> Self {
+                        Self::new()
+                    }
+                }
+            });
+        }
+
+        // Generate the trait for the keys shared with the schemas extending this
+        let is_extended = self.struct_idents.keys().any(|other_id| {
+            self.schema_list
+                .schemas
+                .iter()
+                .find(|other| &other.id == other_id)
+                .is_some_and(|other| {
+                    self.schema_list
+                        .ancestors(other)
+                        .contains(&schema.id.as_str())
+                })
+        });
+        if is_extended {
+            let trait_ident = keys_trait_ident(struct_ident);
+            let trait_docs = format!(
+                "Keys of the `{}` schema, which are shared with the schemas extending it.",
+                schema.id
+            );
+            let vis = self.aux_visibility;
+
+            let mut trait_items_token_stream = proc_macro2::TokenStream::new();
+            for key in &schema.keys {
+                if let GetResult::Some(generator) = self
+                    .key_generators
+                    .get(key, self.aux_visibility.clone())
+                    .unwrap()
+                {
+                    trait_items_token_stream.extend(generator.trait_token_stream());
+                }
+            }
+
+            expanded.extend(quote! {
+                #[doc = #trait_docs]
+                #vis trait #trait_ident: Sized {
+                    #[doc(hidden)]
+                    fn settings(&self) -> &gio::Settings;
+
+                    #[doc(hidden)]
+                    fn from_settings(settings: gio::Settings) -> Self;
+
+                    #trait_items_token_stream
+                }
+            });
+        }
+
+        // Implement its own trait and the traits of the generated schemas it extends
+        for ancestor_id in self
+            .schema_list
+            .ancestors(schema)
+            .into_iter()
+            .chain(is_extended.then_some(schema.id.as_str()))
+        {
+            let Some(ancestor_struct_ident) = self.struct_idents.get(ancestor_id) else {
+                continue;
+            };
+            let trait_ident = keys_trait_ident(ancestor_struct_ident);
+
+            expanded.extend(quote! {
+                impl #trait_ident for #struct_ident {
+                    fn settings(&self) -> &gio::Settings {
+                        &self.0
+                    }
+
+                    fn from_settings(settings: gio::Settings) -> Self {
+                        Self(settings)
+                    }
+                }
+            });
+        }
+
+        expanded
+    }
+}
+
+/// Returns the ident of the trait for the keys shared with the schemas
+/// extending the schema of the given struct (e.g., `SettingsKeys` for `Settings`).
+fn keys_trait_ident(struct_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("{}Keys", struct_ident)
+}
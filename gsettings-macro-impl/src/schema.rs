@@ -0,0 +1,268 @@
+//! The schema file format, which is deserialized from its XML.
+//!
+//! This is also used by the `build` module of `gsettings-macro`, which includes
+//! this file through a symlink, so it must only depend on `serde`.
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaList {
+    #[serde(rename = "enum", default)]
+    pub enums: Vec<Enum>,
+    #[serde(default)]
+    pub flags: Vec<Flag>,
+    #[serde(rename = "schema")]
+    pub schemas: Vec<Schema>,
+}
+
+impl SchemaList {
+    /// Adds the keys and children inherited from the extended schema to each
+    /// schema, with the defaults of the keys replaced by the schema's overrides.
+    pub fn resolve_extends(&mut self) -> Result<(), String> {
+        let resolved = self
+            .schemas
+            .iter()
+            .map(|schema| self.resolved_keys_and_children(schema, &mut Vec::new()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (schema, (keys, children)) in self.schemas.iter_mut().zip(resolved) {
+            schema.keys = keys;
+            schema.children = children;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the ids of the schemas extended by the given schema,
+    /// starting from the one it directly extends.
+    pub fn ancestors<'a>(&'a self, schema: &'a Schema) -> Vec<&'a str> {
+        let mut ancestors = Vec::new();
+
+        let mut current = schema;
+        while let Some(ref base_id) = current.extends {
+            if ancestors.contains(&base_id.as_str()) {
+                break;
+            }
+
+            ancestors.push(base_id.as_str());
+
+            match self.schemas.iter().find(|schema| &schema.id == base_id) {
+                Some(base) => current = base,
+                None => break,
+            }
+        }
+
+        ancestors
+    }
+
+    fn resolved_keys_and_children<'a>(
+        &'a self,
+        schema: &'a Schema,
+        visited: &mut Vec<&'a str>,
+    ) -> Result<(Vec<Key>, Vec<Child>), String> {
+        let Some(ref base_id) = schema.extends else {
+            if let Some(override_) = schema.overrides.first() {
+                return Err(format!(
+                    "schema `{}` overrides key `{}`, but does not extend any schema",
+                    schema.id, override_.name
+                ));
+            }
+
+            return Ok((schema.keys.clone(), schema.children.clone()));
+        };
+
+        visited.push(&schema.id);
+
+        if visited.contains(&base_id.as_str()) {
+            return Err(format!("schema `{}` extends itself", schema.id));
+        }
+
+        let base = self
+            .schemas
+            .iter()
+            .find(|schema| &schema.id == base_id)
+            .ok_or_else(|| {
+                format!(
+                    "schema `{}` extends `{}`, which is not in the schema file",
+                    schema.id, base_id
+                )
+            })?;
+
+        let (mut keys, mut children) = self.resolved_keys_and_children(base, visited)?;
+
+        for override_ in &schema.overrides {
+            let key = keys
+                .iter_mut()
+                .find(|key| key.name == override_.name)
+                .ok_or_else(|| {
+                    format!(
+                        "schema `{}` overrides key `{}`, which is not in the extended schema `{}`",
+                        schema.id, override_.name, base_id
+                    )
+                })?;
+            key.default = override_.default.clone();
+        }
+
+        keys.extend(schema.keys.iter().cloned());
+
+        // The schema's own children replace the inherited ones with the same name
+        children.retain(|child| {
+            !schema
+                .children
+                .iter()
+                .any(|own_child| own_child.name == child.name)
+        });
+        children.extend(schema.children.iter().cloned());
+
+        Ok((keys, children))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Enum {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "value")]
+    pub values: Vec<EnumValues>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnumValues {
+    #[serde(rename = "@nick")]
+    pub nick: String,
+    #[serde(rename = "@value")]
+    pub value: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Flag {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "value")]
+    pub values: Vec<FlagValues>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FlagValues {
+    #[serde(rename = "@nick")]
+    pub nick: String,
+    #[serde(rename = "@value")]
+    pub value: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Schema {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "@path")]
+    pub path: Option<String>,
+    #[serde(rename = "@extends")]
+    pub extends: Option<String>,
+    #[serde(rename = "key", default)]
+    pub keys: Vec<Key>,
+    #[serde(rename = "child", default)]
+    pub children: Vec<Child>,
+    #[serde(rename = "override", default)]
+    pub overrides: Vec<Override>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Override {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "$text")]
+    pub default: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Child {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@schema")]
+    pub schema: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Key {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@type")]
+    type_: Option<String>,
+    #[serde(rename = "@enum")]
+    enum_id: Option<String>,
+    #[serde(rename = "@flags")]
+    flag_id: Option<String>,
+    pub default: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub choices: Option<Choices>,
+    pub range: Option<Range>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+pub enum KeySignature {
+    Type(String),
+    Enum(String),
+    Flag(String),
+}
+
+impl std::fmt::Display for KeySignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeySignature::Type(type_) => write!(f, "`{}` type", type_),
+            KeySignature::Enum(enum_) => write!(f, "`{}` enum", enum_),
+            KeySignature::Flag(flag) => write!(f, "`{}` flag", flag),
+        }
+    }
+}
+
+impl Key {
+    pub fn new(name: String, signature: KeySignature, default: String) -> Self {
+        let (type_, enum_id, flag_id) = match signature {
+            KeySignature::Type(type_) => (Some(type_), None, None),
+            KeySignature::Enum(enum_id) => (None, Some(enum_id), None),
+            KeySignature::Flag(flag_id) => (None, None, Some(flag_id)),
+        };
+
+        Self {
+            name,
+            type_,
+            enum_id,
+            flag_id,
+            default,
+            summary: None,
+            description: None,
+            choices: None,
+            range: None,
+        }
+    }
+
+    pub fn signature(&self) -> Option<KeySignature> {
+        match (&self.type_, &self.enum_id, &self.flag_id) {
+            (Some(type_name), None, None) => Some(KeySignature::Type(type_name.to_string())),
+            (None, Some(enum_id), None) => Some(KeySignature::Enum(enum_id.to_string())),
+            (None, None, Some(flag_id)) => Some(KeySignature::Flag(flag_id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Choice {
+    #[serde(rename = "@value")]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Choices {
+    #[serde(rename = "choice")]
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Range {
+    #[serde(rename = "@max")]
+    pub max: Option<String>,
+    #[serde(rename = "@min")]
+    pub min: Option<String>,
+}
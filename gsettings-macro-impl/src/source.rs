@@ -0,0 +1,188 @@
+//! Parsing of the schema source of `#[gen_settings]`, which consists of its
+//! arguments and the `@placeholder@`s substituted with `#[gen_settings_substitute]`.
+//!
+//! This is also used by the `build` module of `gsettings-macro`, which includes
+//! this file through a symlink, so it must only depend on `deluxe` and `syn`.
+
+use deluxe::SpannedValue;
+use syn::{parse::ParseStream, spanned::Spanned};
+
+#[derive(deluxe::ParseMetaItem)]
+pub struct GenSettings {
+    pub file: Option<SpannedValue<String>>,
+    pub xml: Option<SpannedValue<String>>,
+    pub id: Option<SpannedValue<SchemaIdAttr>>,
+    pub all_schemas: deluxe::Flag,
+    pub bytestring: Option<SpannedValue<String>>,
+    pub glib_types: deluxe::Flag,
+}
+
+/// The schema id, which can be a string literal, an `env!("..")` call,
+/// or a path to a `&'static str` const.
+pub enum SchemaIdAttr {
+    Literal(syn::LitStr),
+    Env(syn::LitStr),
+    Const(syn::Path),
+}
+
+impl SchemaIdAttr {
+    /// Returns the id, if it is known at expansion time, failing if it is
+    /// an `env!("..")` call of an undefined environment variable.
+    pub fn value(&self) -> syn::Result<Option<String>> {
+        match self {
+            Self::Literal(lit) => Ok(Some(lit.value())),
+            Self::Env(name) => std::env::var(name.value()).map(Some).map_err(|_| {
+                syn::Error::new(
+                    name.span(),
+                    format!(
+                        "environment variable `{}` is not defined at compile time",
+                        name.value()
+                    ),
+                )
+            }),
+            Self::Const(_) => Ok(None),
+        }
+    }
+}
+
+impl deluxe::ParseMetaItem for SchemaIdAttr {
+    fn parse_meta_item(input: ParseStream<'_>, _mode: deluxe::ParseMode) -> syn::Result<Self> {
+        match input.parse::<syn::Expr>()? {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Ok(Self::Literal(lit)),
+            syn::Expr::Macro(expr) if expr.mac.path.is_ident("env") => {
+                Ok(Self::Env(expr.mac.parse_body()?))
+            }
+            syn::Expr::Path(expr) => Ok(Self::Const(expr.path)),
+            expr => Err(syn::Error::new(
+                expr.span(),
+                "expected a string literal, `env!(\"..\")`, or a path to a const",
+            )),
+        }
+    }
+}
+
+#[derive(deluxe::ParseAttributes)]
+struct GenSettingsSubstitute {
+    placeholder: SpannedValue<String>,
+    value: Option<SpannedValue<String>>,
+    env: Option<SpannedValue<String>>,
+}
+
+/// A `@placeholder@` and the value it is substituted with.
+pub struct Substitution {
+    pub placeholder: String,
+    pub value: String,
+    /// The environment variable the value is from, if any
+    pub env_name: Option<String>,
+}
+
+impl Substitution {
+    /// Parses a `#[gen_settings_substitute]` attribute, getting the value of
+    /// its environment variable, if any.
+    pub fn from_attr(attr: &syn::Attribute) -> syn::Result<Self> {
+        let GenSettingsSubstitute {
+            placeholder,
+            value,
+            env,
+        } = deluxe::parse_attributes(attr)?;
+
+        let (value, env_name) = match (value, env) {
+            (Some(_), Some(_)) => {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "cannot specify both `value` and `env`",
+                ))
+            }
+            (None, None) => {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "must specify either `value` or `env`",
+                ))
+            }
+            (Some(value), None) => (SpannedValue::into_inner(value), None),
+            (None, Some(env)) => {
+                let env_span = env.span();
+                let env_name = SpannedValue::into_inner(env);
+                let value = std::env::var(&env_name).map_err(|err| {
+                    syn::Error::new(
+                        env_span,
+                        format!("failed to get environment variable `{}`: {}", env_name, err),
+                    )
+                })?;
+                (value, Some(env_name))
+            }
+        };
+
+        Ok(Self {
+            placeholder: SpannedValue::into_inner(placeholder),
+            value,
+            env_name,
+        })
+    }
+}
+
+/// Returns whether the schema is a template, which are the `.in` files and
+/// the schemas with substitutions, as only those are expected to have
+/// placeholders. An `@word@` in the text of other schemas is left as is.
+pub fn is_template(file: Option<&str>, substitutions: &[Substitution]) -> bool {
+    !substitutions.is_empty() || file.is_some_and(|file| file.ends_with(".in"))
+}
+
+/// Substitutes the `@placeholder@`s in the given XML, failing if any is left
+/// in a template.
+pub fn substitute(
+    xml: &str,
+    substitutions: &[Substitution],
+    is_template: bool,
+) -> Result<String, String> {
+    let mut xml = xml.to_string();
+    for substitution in substitutions {
+        xml = xml.replace(
+            &format!("@{}@", substitution.placeholder),
+            &substitution.value,
+        );
+    }
+
+    if !is_template {
+        return Ok(xml);
+    }
+
+    match unsubstituted_placeholders(&xml).as_slice() {
+        [] => Ok(xml),
+        [placeholder] => Err(format!(
+            "the placeholder `@{}@` is not substituted; consider using `#[gen_settings_substitute(placeholder = \"{}\", ..)]`",
+            placeholder, placeholder
+        )),
+        placeholders => Err(format!(
+            "the placeholders {} are not substituted; consider using `#[gen_settings_substitute(placeholder = \"..\", ..)]` for each",
+            placeholders
+                .iter()
+                .map(|placeholder| format!("`@{}@`", placeholder))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Returns the `@placeholder@`s, which consist of alphanumeric characters,
+/// underscores, and dashes, left in the given XML, without duplicates.
+fn unsubstituted_placeholders(xml: &str) -> Vec<&str> {
+    let mut placeholders = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('@') {
+        rest = &rest[start + 1..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if len > 0 && rest[len..].starts_with('@') {
+            if !placeholders.contains(&&rest[..len]) {
+                placeholders.push(&rest[..len]);
+            }
+            rest = &rest[len + 1..];
+        }
+    }
+    placeholders
+}
@@ -0,0 +1,149 @@
+//! Conversions between the types of GSettings keys and common Rust types.
+//!
+//! Each adapter can be used by name with the `adapter` attribute of
+//! `#[gen_settings_define]`, instead of specifying `get_with` and `set_with`:
+//!
+//! ```ignore
+//! use gsettings_macro::gen_settings;
+//!
+//! use std::time::Duration;
+//!
+//! #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+//! #[gen_settings_define(
+//!     key_name = "timeout",
+//!     arg_type = "Duration",
+//!     ret_type = "Duration",
+//!     adapter = "seconds"
+//! )]
+//! pub struct Settings;
+//! ```
+//!
+//! | Adapter       | Type signature | Rust type                                 |
+//! |---------------|----------------|-------------------------------------------|
+//! | `seconds`     | u, t           | [`Duration`]                              |
+//! | `date_time`   | x              | [`glib::DateTime`], in the local timezone |
+//! | `system_time` | x              | [`SystemTime`]                            |
+//! | `from_str`    | s              | Any type that implements [`FromStr`] and [`Display`], like `gdk::RGBA` colours or `url::Url` |
+//! | `uri`         | s              | [`glib::Uri`][uri], with the `uri` feature |
+//! | `path`        | ay             | [`PathBuf`]                               |
+//!
+//! The `uri` adapter requires GLib 2.66, so it is only available with the
+//! `uri` feature.
+//!
+//! Times are stored as the number of seconds since the Unix epoch, and
+//! sub-second precision is dropped.
+//!
+//! [`Display`]: std::fmt::Display
+//! [`Duration`]: std::time::Duration
+//! [`FromStr`]: std::str::FromStr
+//! [`PathBuf`]: std::path::PathBuf
+//! [`SystemTime`]: std::time::SystemTime
+//! [uri]: https://docs.rs/glib/latest/glib/struct.Uri.html
+
+/// `u` or `t` seconds to [`Duration`](std::time::Duration).
+pub mod seconds {
+    use glib::BoolError;
+
+    use std::{convert::Infallible, time::Duration};
+
+    pub fn get(secs: impl Into<u64>) -> Result<Duration, Infallible> {
+        Ok(Duration::from_secs(secs.into()))
+    }
+
+    /// Fails if the seconds do not fit in the key's type.
+    pub fn set<T: TryFrom<u64>>(duration: Duration) -> Result<T, BoolError> {
+        T::try_from(duration.as_secs())
+            .map_err(|_| glib::bool_error!("duration `{:?}` is out of range", duration))
+    }
+}
+
+/// `x` seconds since the Unix epoch to [`glib::DateTime`], in the local timezone.
+pub mod date_time {
+    use glib::{BoolError, DateTime};
+
+    use std::{borrow::Borrow, convert::Infallible};
+
+    pub fn get(secs: i64) -> Result<DateTime, BoolError> {
+        DateTime::from_unix_local(secs)
+    }
+
+    pub fn set(date_time: impl Borrow<DateTime>) -> Result<i64, Infallible> {
+        Ok(date_time.borrow().to_unix())
+    }
+}
+
+/// `x` seconds since the Unix epoch to [`SystemTime`](std::time::SystemTime).
+pub mod system_time {
+    use glib::BoolError;
+
+    use std::time::{Duration, SystemTime};
+
+    pub fn get(secs: i64) -> Result<SystemTime, BoolError> {
+        let duration = Duration::from_secs(secs.unsigned_abs());
+
+        if secs >= 0 {
+            SystemTime::UNIX_EPOCH.checked_add(duration)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_sub(duration)
+        }
+        .ok_or_else(|| glib::bool_error!("time `{}` is out of range", secs))
+    }
+
+    pub fn set(system_time: SystemTime) -> Result<i64, BoolError> {
+        let secs = match system_time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => i64::try_from(duration.as_secs()),
+            Err(err) => i64::try_from(err.duration().as_secs()).map(|secs| -secs),
+        };
+
+        secs.map_err(|_| glib::bool_error!("time `{:?}` is out of range", system_time))
+    }
+}
+
+/// `s` to any type that implements [`FromStr`](std::str::FromStr) and
+/// [`Display`](std::fmt::Display), which is stored as its display string.
+pub mod from_str {
+    use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+    pub fn get<T: FromStr>(string: String) -> Result<T, T::Err> {
+        string.parse()
+    }
+
+    pub fn set(value: impl Display) -> Result<String, Infallible> {
+        Ok(value.to_string())
+    }
+}
+
+/// `s` to [`glib::Uri`].
+#[cfg(feature = "uri")]
+pub mod uri {
+    use glib::{Uri, UriFlags};
+
+    use std::{borrow::Borrow, convert::Infallible};
+
+    pub fn get(string: String) -> Result<Uri, glib::Error> {
+        Uri::parse(&string, UriFlags::NONE)
+    }
+
+    pub fn set(uri: impl Borrow<Uri>) -> Result<String, Infallible> {
+        Ok(uri.borrow().to_str().into())
+    }
+}
+
+/// `ay` to [`PathBuf`](std::path::PathBuf), which also accepts anything that
+/// can be referenced as a path.
+///
+/// The key has to use the `path` or `os_string` bytestring type.
+pub mod path {
+    use std::{
+        convert::Infallible,
+        path::{Path, PathBuf},
+    };
+
+    pub fn get(bytestring: impl Into<PathBuf>) -> Result<PathBuf, Infallible> {
+        Ok(bytestring.into())
+    }
+
+    pub fn set<T: From<PathBuf>>(path: impl AsRef<Path>) -> Result<T, Infallible> {
+        Ok(T::from(path.as_ref().to_path_buf()))
+    }
+}
@@ -0,0 +1,172 @@
+//! Helpers for build scripts.
+//!
+//! The schema XML written inline with `#[gen_settings(xml = "..")]` can be
+//! written out to `.gschema.xml` files from `build.rs`, so it can be compiled
+//! and installed while the Rust source stays the single source of truth:
+//!
+//! ```no_run
+//! // In `build.rs`
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! gsettings_macro::build::write_schema_files("src/settings.rs", out_dir).unwrap();
+//! ```
+//!
+//! This requires the `build` feature, and `gsettings-macro` to be a
+//! build dependency as well.
+
+// Shared with `gsettings-macro-impl` through symlinks, so the schemas are
+// read the same way as `#[gen_settings]` does, of which only a part is used here
+#[allow(dead_code)]
+mod schema;
+#[allow(dead_code)]
+mod source;
+
+use syn::visit::Visit;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use self::{
+    schema::SchemaList,
+    source::{GenSettings, SchemaIdAttr, Substitution},
+};
+
+/// Writes the inline schema XML of every `#[gen_settings]` in the given Rust
+/// source file to a `.gschema.xml` file in the given directory, and returns
+/// the paths of the written files.
+///
+/// As with the `write_schema_file` function of the generated struct, each file
+/// is named after the id of its schema, which is the `id` of `#[gen_settings]`,
+/// if it is a string literal or `env!(..)`, or the last schema in the XML
+/// otherwise. The `@placeholder@`s of `#[gen_settings_substitute]` are
+/// substituted too, and as with the macro, any placeholder left in a schema
+/// with substitutions is an error.
+///
+/// This also tells cargo to rerun the build script when the source file or the
+/// environment variables used for the ids and substitutions change.
+pub fn write_schema_files(
+    source: impl AsRef<Path>,
+    dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let source = source.as_ref();
+    println!("cargo:rerun-if-changed={}", source.display());
+
+    let file = syn::parse_file(&fs::read_to_string(source)?)
+        .map_err(|err| invalid_data(format!("failed to parse `{}`: {}", source.display(), err)))?;
+
+    let mut visitor = InlineSchemaVisitor::default();
+    visitor.visit_file(&file);
+    if let Some(err) = visitor.error {
+        return Err(err);
+    }
+
+    visitor
+        .schemas
+        .into_iter()
+        .map(|(id, xml)| {
+            let path = dir.as_ref().join(format!("{}.gschema.xml", id));
+            fs::write(&path, xml)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Collects the schema ids and the substituted XML of the inline schemas.
+#[derive(Default)]
+struct InlineSchemaVisitor {
+    schemas: Vec<(String, String)>,
+    error: Option<io::Error>,
+}
+
+impl<'ast> Visit<'ast> for InlineSchemaVisitor {
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        if self.error.is_none() {
+            match inline_schema(&item.attrs) {
+                Ok(Some(schema)) => self.schemas.push(schema),
+                Ok(None) => {}
+                Err(err) => self.error = Some(err),
+            }
+        }
+
+        syn::visit::visit_item_struct(self, item);
+    }
+}
+
+/// Returns the schema id and the substituted XML of the inline schema in the
+/// given attributes, if any.
+fn inline_schema(attrs: &[syn::Attribute]) -> io::Result<Option<(String, String)>> {
+    let Some(gen_settings) = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("gen_settings"))
+    else {
+        return Ok(None);
+    };
+
+    let tokens = gen_settings
+        .meta
+        .require_list()
+        .map_err(syn_error)?
+        .tokens
+        .clone();
+    let GenSettings { xml, id, .. } = deluxe::parse2(tokens).map_err(syn_error)?;
+    let Some(xml) = xml else {
+        return Ok(None);
+    };
+
+    let substitutions = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("gen_settings_substitute"))
+        .map(Substitution::from_attr)
+        .collect::<syn::Result<Vec<_>>>()
+        .map_err(syn_error)?;
+    for substitution in &substitutions {
+        if let Some(ref env_name) = substitution.env_name {
+            println!("cargo:rerun-if-env-changed={}", env_name);
+        }
+    }
+    if let Some(SchemaIdAttr::Env(ref env_name)) = id.as_deref() {
+        println!("cargo:rerun-if-env-changed={}", env_name.value());
+    }
+
+    let is_template = source::is_template(None, &substitutions);
+    let xml = source::substitute(&xml, &substitutions, is_template).map_err(invalid_data)?;
+
+    let mut schema_list = quick_xml::de::from_str::<SchemaList>(&xml)
+        .map_err(|err| invalid_data(format!("failed to parse schema: {}", err)))?;
+    schema_list
+        .resolve_extends()
+        .map_err(|err| invalid_data(format!("failed to resolve schema: {}", err)))?;
+
+    // As with `#[gen_settings]`, the last schema is used if the id is not known
+    let known_id = match id {
+        Some(id) => id.value().map_err(syn_error)?,
+        None => None,
+    };
+    let schema = match known_id {
+        Some(id) => schema_list
+            .schemas
+            .iter()
+            .find(|schema| schema.id == id)
+            .ok_or_else(|| {
+                invalid_data(format!(
+                    "id `{}` does not match any schema specified in the inline XML",
+                    id
+                ))
+            })?,
+        None => schema_list
+            .schemas
+            .last()
+            .ok_or_else(|| invalid_data("found no schema in the inline XML"))?,
+    };
+
+    Ok(Some((schema.id.clone(), xml)))
+}
+
+fn syn_error(err: syn::Error) -> io::Error {
+    invalid_data(err.to_string())
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
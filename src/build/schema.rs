@@ -0,0 +1 @@
+../../gsettings-macro-impl/src/schema.rs
\ No newline at end of file
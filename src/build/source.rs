@@ -0,0 +1 @@
+../../gsettings-macro-impl/src/source.rs
\ No newline at end of file
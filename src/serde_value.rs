@@ -0,0 +1,216 @@
+use glib::{
+    variant::{ObjectPath, Signature},
+    BoolError, Variant, VariantTy,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+
+/// Serializes `value` as a JSON string variant.
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<Variant, BoolError> {
+    let json = serde_json::to_string(value)
+        .map_err(|err| glib::bool_error!("failed to serialize value: {}", err))?;
+    Ok(Variant::from(json))
+}
+
+/// Deserializes a JSON string variant.
+pub fn from_json<T: DeserializeOwned>(variant: &Variant) -> Result<T, BoolError> {
+    let json = variant
+        .str()
+        .ok_or_else(|| glib::bool_error!("expected a string, found `{}`", variant.type_()))?;
+    serde_json::from_str(json)
+        .map_err(|err| glib::bool_error!("failed to deserialize value: {}", err))
+}
+
+/// Serializes `value` as a variant of the given type.
+///
+/// Structs with named fields are stored as dictionaries keyed by the field
+/// names, while tuples and tuple structs are stored as tuples.
+pub fn to_variant<T: Serialize + ?Sized>(
+    value: &T,
+    type_: &VariantTy,
+) -> Result<Variant, BoolError> {
+    let value = serde_json::to_value(value)
+        .map_err(|err| glib::bool_error!("failed to serialize value: {}", err))?;
+    value_to_variant(&value, type_)
+}
+
+/// Deserializes a variant of any type.
+pub fn from_variant<T: DeserializeOwned>(variant: &Variant) -> Result<T, BoolError> {
+    let value = variant_to_value(variant)?;
+    serde_json::from_value(value)
+        .map_err(|err| glib::bool_error!("failed to deserialize value: {}", err))
+}
+
+fn value_to_variant(value: &Value, type_: &VariantTy) -> Result<Variant, BoolError> {
+    let mismatch = || {
+        glib::bool_error!(
+            "expected a value that can be stored as `{}`, found `{}`",
+            type_,
+            value
+        )
+    };
+
+    let variant = match (type_.as_str().as_bytes()[0], value) {
+        (b'b', Value::Bool(value)) => Variant::from(*value),
+        (b'y' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'h' | b'd', Value::Number(number)) => {
+            number_to_variant(number, type_).ok_or_else(mismatch)?
+        }
+        (b's', Value::String(string)) => Variant::from(string.as_str()),
+        (b'o', Value::String(string)) => {
+            Variant::from(ObjectPath::try_from(string.as_str()).map_err(|_| mismatch())?)
+        }
+        (b'g', Value::String(string)) => {
+            Variant::from(Signature::try_from(string.as_str()).map_err(|_| mismatch())?)
+        }
+        (b'v', value) => Variant::from_variant(&value_to_variant(value, &inferred_type(value)?)?),
+        (b'm', Value::Null) => Variant::from_none(type_.element()),
+        (b'm', value) => Variant::from_some(&value_to_variant(value, type_.element())?),
+        (b'a', Value::Object(map)) if type_.element().is_dict_entry() => {
+            let entry_type = type_.element();
+            let entries = map
+                .iter()
+                .map(|(key, value)| {
+                    Ok(Variant::from_dict_entry(
+                        &key_to_variant(key, entry_type.key()).ok_or_else(mismatch)?,
+                        &value_to_variant(value, entry_type.value())?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, BoolError>>()?;
+            Variant::array_from_iter_with_type(entry_type, entries)
+        }
+        (b'a', Value::Array(elements)) => {
+            let children = elements
+                .iter()
+                .map(|element| value_to_variant(element, type_.element()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Variant::array_from_iter_with_type(type_.element(), children)
+        }
+        (b'(', Value::Null) if type_.n_items() == 0 => {
+            Variant::tuple_from_iter(Vec::<Variant>::new())
+        }
+        (b'(', Value::Array(fields)) => {
+            tuple_to_variant(fields.iter(), type_).ok_or_else(mismatch)??
+        }
+        // The order of the fields is lost in the map, so they can't be
+        // matched to the members of the tuple
+        (b'(', Value::Object(_)) => {
+            return Err(glib::bool_error!(
+                "structs with named fields can't be stored as `{}`; consider using a tuple struct or a dictionary type",
+                type_
+            ))
+        }
+        _ => return Err(mismatch()),
+    };
+
+    Ok(variant)
+}
+
+/// Returns `None` if the number of fields does not match the tuple type.
+fn tuple_to_variant<'a>(
+    fields: impl ExactSizeIterator<Item = &'a Value>,
+    type_: &VariantTy,
+) -> Option<Result<Variant, BoolError>> {
+    if fields.len() != type_.n_items() {
+        return None;
+    }
+
+    let children = fields
+        .zip(type_.tuple_types())
+        .map(|(field, field_type)| value_to_variant(field, field_type))
+        .collect::<Result<Vec<_>, _>>();
+    Some(children.map(Variant::tuple_from_iter))
+}
+
+/// Parses a JSON object key as a dictionary key of the given basic type.
+fn key_to_variant(key: &str, type_: &VariantTy) -> Option<Variant> {
+    let value = match type_.as_str() {
+        "s" | "o" | "g" => Value::String(key.to_string()),
+        "b" => Value::Bool(key.parse().ok()?),
+        _ => Value::Number(key.parse().ok()?),
+    };
+    value_to_variant(&value, type_).ok()
+}
+
+/// Returns the type used to store a value in a `v` variant.
+fn inferred_type(value: &Value) -> Result<glib::VariantType, BoolError> {
+    let type_ = match value {
+        Value::Null => return Err(glib::bool_error!("can't store `null` in a variant")),
+        Value::Bool(_) => VariantTy::BOOLEAN,
+        Value::Number(number) if number.is_i64() => VariantTy::INT64,
+        Value::Number(number) if number.is_u64() => VariantTy::UINT64,
+        Value::Number(_) => VariantTy::DOUBLE,
+        Value::String(_) => VariantTy::STRING,
+        Value::Array(_) => return Ok(glib::VariantType::new_array(VariantTy::VARIANT)),
+        Value::Object(_) => VariantTy::VARDICT,
+    };
+    Ok(type_.to_owned())
+}
+
+/// Returns `None` if the number is out of the range of the given numeric type.
+fn number_to_variant(number: &Number, type_: &VariantTy) -> Option<Variant> {
+    fn integer<T: TryFrom<i64>>(number: &Number) -> Option<T> {
+        T::try_from(number.as_i64()?).ok()
+    }
+
+    let variant = match type_.as_str() {
+        "y" => Variant::from(integer::<u8>(number)?),
+        "n" => Variant::from(integer::<i16>(number)?),
+        "q" => Variant::from(integer::<u16>(number)?),
+        "i" => Variant::from(integer::<i32>(number)?),
+        "u" => Variant::from(integer::<u32>(number)?),
+        "x" => Variant::from(number.as_i64()?),
+        "t" => Variant::from(number.as_u64()?),
+        "h" => Variant::from(glib::variant::Handle(integer::<i32>(number)?)),
+        "d" => Variant::from(number.as_f64()?),
+        _ => return None,
+    };
+
+    Some(variant)
+}
+
+fn variant_to_value(variant: &Variant) -> Result<Value, BoolError> {
+    let type_ = variant.type_();
+
+    let value = match type_.as_str().as_bytes()[0] {
+        b'b' => Value::Bool(variant.get::<bool>().unwrap()),
+        b'y' => Value::from(variant.get::<u8>().unwrap()),
+        b'n' => Value::from(variant.get::<i16>().unwrap()),
+        b'q' => Value::from(variant.get::<u16>().unwrap()),
+        b'i' => Value::from(variant.get::<i32>().unwrap()),
+        b'u' => Value::from(variant.get::<u32>().unwrap()),
+        b'x' => Value::from(variant.get::<i64>().unwrap()),
+        b't' => Value::from(variant.get::<u64>().unwrap()),
+        b'h' => Value::from(variant.get::<glib::variant::Handle>().unwrap().0),
+        b'd' => Number::from_f64(variant.get::<f64>().unwrap())
+            .map(Value::Number)
+            .ok_or_else(|| glib::bool_error!("can't deserialize `{}`", variant))?,
+        b's' | b'o' | b'g' => Value::from(variant.str().unwrap()),
+        b'v' => variant_to_value(&variant.as_variant().unwrap())?,
+        b'm' => match variant.as_maybe() {
+            Some(child) => variant_to_value(&child)?,
+            None => Value::Null,
+        },
+        b'a' if type_.element().is_dict_entry() => Value::Object(
+            variant
+                .iter()
+                .map(|entry| {
+                    let key = entry.child_value(0);
+                    let key = match key.str() {
+                        Some(key) => key.to_string(),
+                        None => variant_to_value(&key)?.to_string(),
+                    };
+                    Ok((key, variant_to_value(&entry.child_value(1))?))
+                })
+                .collect::<Result<Map<_, _>, BoolError>>()?,
+        ),
+        b'a' | b'(' => Value::Array(
+            variant
+                .iter()
+                .map(|child| variant_to_value(&child))
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(glib::bool_error!("can't deserialize `{}`", type_)),
+    };
+
+    Ok(value)
+}
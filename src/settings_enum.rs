@@ -0,0 +1,130 @@
+/// A Rust enum whose variants are stored as the nicks of a schema `<enum>`.
+///
+/// It is implemented with `#[derive(SettingsEnum)]`, which also implements
+/// the variant conversions, so the enum can be used in place of a generated
+/// one with `#[gen_settings_enum]`. The nick of each variant defaults to its
+/// name in kebab case, and can be overridden with `#[nick = ".."]`.
+///
+/// ```ignore
+/// use gsettings_macro::SettingsEnum;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, SettingsEnum)]
+/// pub enum AlertSound {
+///     Bark = 0,
+///     Glass = 2,
+///     #[nick = "drip"]
+///     WaterDrip = 1,
+/// }
+/// ```
+///
+/// The nicks and values are checked against the schema at compile time, so
+/// an enum that doesn't match the definition fails to compile:
+///
+/// ```compile_fail,E0080
+/// use gio::glib;
+/// use gsettings_macro::{gen_settings, SettingsEnum};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, SettingsEnum)]
+/// pub enum Sound {
+///     Bark = 0,
+///     Glass = 1,
+///     Drip = 2,
+/// }
+///
+/// #[gen_settings(
+///     xml = r#"
+///         <schemalist>
+///             <enum id="org.example.Sound">
+///                 <value nick="bark" value="0"/>
+///                 <value nick="glass" value="2"/>
+///                 <value nick="drip" value="1"/>
+///             </enum>
+///             <schema id="org.example" path="/org/example/">
+///                 <key name="sound" enum="org.example.Sound">
+///                     <default>'bark'</default>
+///                 </key>
+///             </schema>
+///         </schemalist>
+///     "#
+/// )]
+/// #[gen_settings_enum(id = "org.example.Sound", type = "Sound")]
+/// pub struct Settings;
+/// ```
+pub trait SettingsEnum: Sized {
+    /// The nick and value of each variant, in declaration order.
+    const VALUES: &'static [(&'static str, i32)];
+}
+
+/// A flags type whose flags are stored as the nicks of a schema `<flags>`.
+///
+/// It is implemented by the flags generated by the macros, so they can be
+/// shared between settings structs with `#[gen_settings_enum]`. Other types
+/// need to implement it along with [`ToVariant`], [`FromVariant`], and
+/// [`StaticVariantType`] as a string array of nicks.
+///
+/// [`ToVariant`]: glib::variant::ToVariant
+/// [`FromVariant`]: glib::variant::FromVariant
+/// [`StaticVariantType`]: glib::variant::StaticVariantType
+pub trait SettingsFlags: Sized {
+    /// The nick and value of each flag, in declaration order.
+    const VALUES: &'static [(&'static str, u32)];
+}
+
+/// Panics with `message` if `actual` does not have the `expected` values,
+/// in any order.
+macro_rules! check_values {
+    ($actual:expr, $expected:expr, $message:expr) => {{
+        let (actual, expected) = ($actual, $expected);
+
+        if actual.len() != expected.len() {
+            panic!("{}", $message);
+        }
+
+        let mut i = 0;
+        while i < expected.len() {
+            let mut found = false;
+
+            let mut j = 0;
+            while j < actual.len() {
+                if actual[j].1 == expected[i].1 && str_eq(actual[j].0, expected[i].0) {
+                    found = true;
+                }
+                j += 1;
+            }
+
+            if !found {
+                panic!("{}", $message);
+            }
+            i += 1;
+        }
+    }};
+}
+
+/// Panics with `message` if the values of `T` are not the `expected` ones,
+/// in any order, which fails the compilation when used in a constant.
+pub const fn check_enum_values<T: SettingsEnum>(expected: &[(&str, i32)], message: &str) {
+    check_values!(T::VALUES, expected, message);
+}
+
+/// Like [`check_enum_values`], but for flags.
+pub const fn check_flags_values<T: SettingsFlags>(expected: &[(&str, u32)], message: &str) {
+    check_values!(T::VALUES, expected, message);
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
@@ -0,0 +1,120 @@
+use glib::{
+    variant::{FromVariant, StaticVariantType, ToVariant},
+    BoolError, Variant, VariantTy,
+};
+
+use std::borrow::Cow;
+
+/// A value that can be stored in a GSettings key.
+///
+/// The generated getters and setters convert values with this trait, so
+/// types that are used in `gen_settings_define` need to implement it.
+///
+/// It is implemented for every type that implements [`StaticVariantType`],
+/// [`FromVariant`], and [`ToVariant`]. Other types, like newtypes that
+/// validate their values, can implement it directly.
+///
+/// ```
+/// use gsettings_macro::SettingsValue;
+/// use glib::{Variant, VariantTy};
+///
+/// use std::borrow::Cow;
+///
+/// struct Percent(u8);
+///
+/// impl SettingsValue for Percent {
+///     fn variant_type() -> Cow<'static, VariantTy> {
+///         Cow::Borrowed(VariantTy::BYTE)
+///     }
+///
+///     fn from_variant(variant: &Variant) -> Result<Self, glib::BoolError> {
+///         match variant.get::<u8>() {
+///             Some(value) if value <= 100 => Ok(Self(value)),
+///             _ => Err(glib::bool_error!("invalid percent `{}`", variant)),
+///         }
+///     }
+///
+///     fn to_variant(&self) -> Result<Variant, glib::BoolError> {
+///         Ok(Variant::from(self.0))
+///     }
+/// }
+/// ```
+pub trait SettingsValue: Sized {
+    /// Returns the type of the variant the value is stored as.
+    fn variant_type() -> Cow<'static, VariantTy>;
+
+    /// Converts a variant of [`variant_type`](Self::variant_type) to the value.
+    fn from_variant(variant: &Variant) -> Result<Self, BoolError>;
+
+    /// Converts the value to a variant of [`variant_type`](Self::variant_type).
+    fn to_variant(&self) -> Result<Variant, BoolError>;
+}
+
+impl<T> SettingsValue for T
+where
+    T: StaticVariantType + FromVariant + ToVariant,
+{
+    fn variant_type() -> Cow<'static, VariantTy> {
+        T::static_variant_type()
+    }
+
+    fn from_variant(variant: &Variant) -> Result<Self, BoolError> {
+        variant
+            .get()
+            .ok_or_else(|| glib::bool_error!("invalid value `{}`", variant))
+    }
+
+    fn to_variant(&self) -> Result<Variant, BoolError> {
+        Ok(ToVariant::to_variant(self))
+    }
+}
+
+/// Converts a variant to `T`, failing if the variant has a different type.
+pub fn from_variant<T: SettingsValue>(variant: &Variant) -> Result<T, BoolError> {
+    let expected = T::variant_type();
+
+    if !variant.is_type(&expected) {
+        return Err(glib::bool_error!(
+            "expected value of type `{}`, found `{}`",
+            expected,
+            variant.type_()
+        ));
+    }
+
+    T::from_variant(variant)
+}
+
+/// Converts `value` to a variant, failing if it has a different type than
+/// the one declared by `T`.
+pub fn to_variant<T: SettingsValue>(value: &T) -> Result<Variant, BoolError> {
+    let expected = T::variant_type();
+    let variant = value.to_variant()?;
+
+    if !variant.is_type(&expected) {
+        return Err(glib::bool_error!(
+            "expected value of type `{}`, converted to `{}`",
+            expected,
+            variant.type_()
+        ));
+    }
+
+    Ok(variant)
+}
+
+/// Converts a setter's parameter, which is a borrowed or otherwise different
+/// form of `T`, like `&str` for `String`, to a variant, failing if it has a
+/// different type than the one declared by `T`.
+pub fn param_to_variant<T: SettingsValue>(value: impl Into<Variant>) -> Result<Variant, BoolError> {
+    let expected = T::variant_type();
+    let variant = value.into();
+
+    if !variant.is_type(&expected) {
+        return Err(glib::bool_error!(
+            "expected value of type `{}`, found `{}`",
+            expected,
+            variant.type_()
+        ));
+    }
+
+    Ok(variant)
+}
@@ -6,6 +6,7 @@ use gsettings_macro::gen_settings;
 
 use std::{
     cell::Cell,
+    collections::{BTreeMap, HashMap},
     env,
     path::{Path, PathBuf},
     process::Command,
@@ -88,6 +89,248 @@ fn setter_and_getter_func() {
     assert_eq!(settings.dimensions(), (20, 20));
 }
 
+#[test]
+#[serial_test::serial]
+fn tuple_and_array_signatures() {
+    setup_schema();
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    settings.set_string_tuple(("a", "b"));
+    assert_eq!(settings.string_tuple(), ("a".to_string(), "b".to_string()));
+
+    assert_eq!(settings.scale(), (1.0, 1.0));
+    settings.set_scale((2.0, 0.5));
+    assert_eq!(settings.scale(), (2.0, 0.5));
+
+    assert_eq!(settings.recent_points(), vec![(0, 0)]);
+    settings.set_recent_points(&[(1, 2), (3, 4)]);
+    assert_eq!(settings.recent_points(), vec![(1, 2), (3, 4)]);
+    settings.set_recent_points(&[]);
+    assert_eq!(settings.recent_points(), vec![]);
+
+    assert_eq!(settings.word_groups(), vec![vec!["hello", "world"]]);
+    settings.set_word_groups(&[&["a"], &["b", "c"]]);
+    assert_eq!(settings.word_groups(), vec![vec!["a"], vec!["b", "c"]]);
+
+    assert_eq!(settings.last_file(), (String::new(), 0, false));
+    settings.set_last_file(("/tmp/file", 10, true));
+    assert_eq!(settings.last_file(), ("/tmp/file".to_string(), 10, true));
+}
+
+#[test]
+#[serial_test::serial]
+fn dictionary_signatures() {
+    setup_schema();
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_skip(signature = "ay")]
+    #[gen_settings_define(
+        key_name = "aliases",
+        arg_type = "&BTreeMap<String, String>",
+        ret_type = "BTreeMap<String, String>"
+    )]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(
+        settings.column_widths(),
+        HashMap::from([("name".to_string(), 100)])
+    );
+    settings.set_column_widths([("name", 200), ("size", 50)]);
+    assert_eq!(
+        settings.column_widths(),
+        HashMap::from([("name".to_string(), 200), ("size".to_string(), 50)])
+    );
+    let widths = HashMap::from([("date".to_string(), 10)]);
+    settings.set_column_widths(widths.iter().map(|(k, v)| (k.as_str(), *v)));
+    assert_eq!(settings.column_widths(), widths);
+    settings.set_column_widths([]);
+    assert!(settings.column_widths().is_empty());
+
+    assert!(settings.aliases().is_empty());
+    let aliases = BTreeMap::from([("ll".to_string(), "ls -l".to_string())]);
+    settings.set_aliases(&aliases);
+    assert_eq!(settings.aliases(), aliases);
+
+    assert_eq!(
+        settings.window_state(),
+        HashMap::from([("is-fullscreen".to_string(), false.to_variant())])
+    );
+    settings.set_window_state([("width", &600.to_variant()), ("title", &"Hi".to_variant())]);
+    let window_state = settings.window_state();
+    assert_eq!(window_state["width"].get::<i32>(), Some(600));
+    assert_eq!(window_state["title"].get::<String>().as_deref(), Some("Hi"));
+}
+
+#[test]
+fn dictionary_with_nested_references() {
+    #[gen_settings(xml = r#"
+            <schemalist>
+                <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+                    <key name="groups" type="a{sas}">
+                        <default>{}</default>
+                    </key>
+                </schema>
+            </schemalist>
+        "#)]
+    pub struct Settings;
+
+    // Each reference in the entries gets the lifetime of the setter
+    let _ = |settings: &Settings, members: Vec<&str>| {
+        settings.set_groups([("admins", members.as_slice())]);
+    };
+}
+
+#[test]
+#[serial_test::serial]
+fn maybe_signatures() {
+    setup_schema();
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(settings.last_search(), None);
+    assert_eq!(settings.last_search_default_value(), None);
+    settings.set_last_search(Some("query"));
+    assert_eq!(settings.last_search().as_deref(), Some("query"));
+    settings.set_last_search(None);
+    assert_eq!(settings.last_search(), None);
+
+    assert_eq!(settings.saved_position(), Some((1, 2)));
+    assert_eq!(settings.saved_position_default_value(), Some((1, 2)));
+    settings.set_saved_position(None);
+    assert_eq!(settings.saved_position(), None);
+
+    assert_eq!(settings.extra_value(), None);
+    settings.set_extra_value(Some(&"hi".to_variant()));
+    assert_eq!(settings.extra_value(), Some("hi".to_variant()));
+}
+
+#[test]
+#[serial_test::serial]
+fn basic_signatures() {
+    use glib::variant::{ObjectPath, Signature};
+
+    setup_schema();
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(settings.opacity(), 255);
+    settings.set_opacity(128);
+    assert_eq!(settings.opacity(), 128);
+
+    assert_eq!(settings.offset(), -1);
+    settings.set_offset(i16::MIN);
+    assert_eq!(settings.offset(), i16::MIN);
+
+    assert_eq!(settings.port(), 8080);
+    settings.set_port(u16::MAX);
+    assert_eq!(settings.port(), u16::MAX);
+
+    assert_eq!(settings.object_path().as_str(), "/io/github/seadve/test");
+    settings.set_object_path(&ObjectPath::try_from("/some/path").unwrap());
+    assert_eq!(settings.object_path().as_str(), "/some/path");
+
+    assert_eq!(settings.value_signature().as_str(), "s");
+    settings.set_value_signature(&Signature::try_from("(ii)").unwrap());
+    assert_eq!(settings.value_signature().as_str(), "(ii)");
+
+    assert_eq!(settings.stored_value(), "hello".to_variant());
+    assert_eq!(settings.stored_value_default_value(), "hello".to_variant());
+    settings.set_stored_value(&(1, 2).to_variant());
+    assert_eq!(settings.stored_value().get::<(i32, i32)>(), Some((1, 2)));
+}
+
+#[test]
+#[serial_test::serial]
+fn bytestring_signature() {
+    use std::ffi::{OsStr, OsString};
+
+    setup_schema();
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    pub struct PathSettings;
+
+    // Separate modules as the generated enums would conflict
+    mod os_string {
+        use super::*;
+
+        #[gen_settings(
+            file = "tests/io.github.seadve.test.gschema.xml",
+            bytestring = "os_string"
+        )]
+        pub struct OsStringSettings;
+    }
+
+    mod bytes {
+        use super::*;
+
+        #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml", bytestring = "bytes")]
+        pub struct BytesSettings;
+    }
+
+    mod raw_bytes {
+        use super::*;
+
+        #[gen_settings(
+            file = "tests/io.github.seadve.test.gschema.xml",
+            bytestring = "raw_bytes"
+        )]
+        pub struct RawBytesSettings;
+    }
+
+    let settings = PathSettings::new("io.github.seadve.test");
+    let raw_value = || settings.value("cache-dir").get::<Vec<u8>>().unwrap();
+
+    assert_eq!(settings.cache_dir(), PathBuf::from("/tmp/cache_dir/"));
+    assert_eq!(
+        settings.cache_dir_default_value(),
+        PathBuf::from("/tmp/cache_dir/")
+    );
+    settings.set_cache_dir(Path::new("/some/dir/"));
+    assert_eq!(settings.cache_dir(), PathBuf::from("/some/dir/"));
+    assert_eq!(raw_value(), b"/some/dir/\0");
+
+    let os_string_settings = os_string::OsStringSettings::new("io.github.seadve.test");
+    assert_eq!(os_string_settings.cache_dir(), OsString::from("/some/dir/"));
+    os_string_settings.set_cache_dir(OsStr::new("/other/dir/"));
+    assert_eq!(
+        os_string_settings.cache_dir(),
+        OsString::from("/other/dir/")
+    );
+    assert_eq!(raw_value(), b"/other/dir/\0");
+
+    let bytes_settings = bytes::BytesSettings::new("io.github.seadve.test");
+    assert_eq!(bytes_settings.cache_dir(), b"/other/dir/");
+    assert_eq!(bytes_settings.cache_dir_default_value(), b"/tmp/cache_dir/");
+    bytes_settings.set_cache_dir(b"/bytes/");
+    assert_eq!(bytes_settings.cache_dir(), b"/bytes/");
+    assert_eq!(raw_value(), b"/bytes/\0");
+    assert_eq!(settings.cache_dir(), PathBuf::from("/bytes/"));
+
+    // Binary data is stored as is
+    let raw_bytes_settings = raw_bytes::RawBytesSettings::new("io.github.seadve.test");
+    assert_eq!(raw_bytes_settings.cache_dir(), b"/bytes/\0");
+    raw_bytes_settings.set_cache_dir(&[1, 0]);
+    assert_eq!(raw_bytes_settings.cache_dir(), [1, 0]);
+    assert_eq!(raw_value(), [1, 0]);
+
+    settings.reset_cache_dir();
+}
+
 #[test]
 #[serial_test::serial]
 fn create_action_func() {
@@ -188,7 +431,7 @@ fn default_value_func() {
         PathBuf::from("/tmp/cache_dir/")
     );
     assert_eq!(settings.alert_sound_default_value(), AlertSound::Bark);
-    assert_eq!(settings.space_style_default_value(), SpaceStyle::empty());
+    assert_eq!(settings.space_style_default_value(), SpacesStyle::empty());
 }
 
 #[test]
@@ -282,195 +525,649 @@ fn custom_define_key_name() {
 
 #[test]
 #[serial_test::serial]
-fn overlapping_define() {
+fn custom_define_with_conversion() {
+    use std::{
+        net::{AddrParseError, SocketAddr},
+        time::Duration,
+    };
+
     setup_schema();
 
-    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
-    #[gen_settings_define(signature = "ay", arg_type = "&OsStr", ret_type = "OsString")]
-    #[gen_settings_define(key_name = "cache-dir", arg_type = "&Path", ret_type = "PathBuf")]
-    #[gen_settings_skip(signature = "(ss)")]
+    fn duration_from_secs(secs: u32) -> Duration {
+        Duration::from_secs(secs as u64)
+    }
+
+    fn duration_to_secs(duration: Duration) -> u32 {
+        duration.as_secs() as u32
+    }
+
+    fn parse_address(address: String) -> Result<SocketAddr, AddrParseError> {
+        address.parse()
+    }
+
+    fn address_to_string(address: &SocketAddr) -> Result<String, &'static str> {
+        if address.port() == 0 {
+            return Err("port must not be zero");
+        }
+
+        Ok(address.to_string())
+    }
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_define(
+        key_name = "timeout",
+        arg_type = "Duration",
+        ret_type = "Duration",
+        get_with = "duration_from_secs",
+        set_with = "duration_to_secs"
+    )]
+    #[gen_settings_define(
+        key_name = "server-address",
+        arg_type = "&SocketAddr",
+        ret_type = "SocketAddr",
+        get_with = "parse_address",
+        set_with = "address_to_string",
+        fallible
+    )]
     pub struct Settings;
 
     let settings = Settings::new("io.github.seadve.test");
-    settings.set_cache_dir(Path::new("/some_dir"));
-    assert_eq!(settings.cache_dir(), PathBuf::from("/some_dir"));
+
+    assert_eq!(settings.timeout(), Duration::from_secs(30));
+    assert_eq!(settings.timeout_default_value(), Duration::from_secs(30));
+    settings.set_timeout(Duration::from_secs(60));
+    assert_eq!(settings.timeout(), Duration::from_secs(60));
+    assert_eq!(settings.uint("timeout"), 60);
+
+    let default_address = "127.0.0.1:8080".parse::<SocketAddr>().unwrap();
+    assert_eq!(settings.server_address(), default_address);
+    assert_eq!(settings.server_address_default_value(), default_address);
+
+    let address = "192.168.1.1:3000".parse::<SocketAddr>().unwrap();
+    settings.set_server_address(&address);
+    assert_eq!(settings.try_server_address().unwrap(), address);
+    assert_eq!(settings.string("server-address"), "192.168.1.1:3000");
+
+    let err = settings
+        .try_set_server_address(&"192.168.1.1:0".parse().unwrap())
+        .unwrap_err();
+    assert!(err.message.contains("port must not be zero"));
+    assert_eq!(settings.server_address(), address);
+
+    settings.set_string("server-address", "invalid").unwrap();
+    assert!(settings.try_server_address().is_err());
 }
 
 #[test]
 #[serial_test::serial]
-fn string_choice_enum() {
+fn custom_settings_value() {
+    use gsettings_macro::SettingsValue;
+    use std::borrow::Cow;
+
     setup_schema();
 
-    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
-    #[gen_settings_skip(signature = "(ss)")]
-    #[gen_settings_skip(signature = "ay")]
-    pub struct Settings;
+    #[derive(Debug, PartialEq)]
+    pub struct Percent(u8);
 
-    assert_eq!(
-        PreferredAudioSource::static_variant_type(),
-        gio::glib::VariantTy::STRING
-    );
+    impl SettingsValue for Percent {
+        fn variant_type() -> Cow<'static, VariantTy> {
+            Cow::Borrowed(VariantTy::BYTE)
+        }
 
-    assert_eq!(
-        PreferredAudioSource::DesktopAudio
-            .to_variant()
-            .get::<String>()
-            .unwrap(),
-        "desktop-audio"
-    );
-    assert_eq!(
-        PreferredAudioSource::Microphone
-            .to_variant()
-            .get::<String>()
-            .unwrap(),
-        "microphone"
-    );
+        fn from_variant(variant: &glib::Variant) -> Result<Self, glib::BoolError> {
+            match variant.get::<u8>() {
+                Some(value) if value <= 100 => Ok(Self(value)),
+                _ => Err(glib::bool_error!("invalid percent `{}`", variant)),
+            }
+        }
 
-    assert_eq!(
-        PreferredAudioSource::DesktopAudio,
-        PreferredAudioSource::from_variant(&"desktop-audio".to_variant()).unwrap()
-    );
-    assert_eq!(
-        PreferredAudioSource::Microphone,
-        PreferredAudioSource::from_variant(&"microphone".to_variant()).unwrap()
-    );
-}
+        fn to_variant(&self) -> Result<glib::Variant, glib::BoolError> {
+            if self.0 > 100 {
+                return Err(glib::bool_error!("invalid percent `{}`", self.0));
+            }
 
-#[test]
-#[serial_test::serial]
-fn enumeration() {
-    setup_schema();
+            Ok(glib::Variant::from(self.0))
+        }
+    }
 
-    #[gen_settings(
-        file = "./tests/io.github.seadve.test.gschema.xml",
-        id = "io.github.seadve.test"
-    )]
-    #[gen_settings_skip(signature = "(ss)")]
-    #[gen_settings_skip(signature = "ay")]
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_define(key_name = "opacity", arg_type = "&Percent", ret_type = "Percent")]
+    #[gen_settings_define(key_name = "offset", arg_type = "u32", ret_type = "u32")]
     pub struct Settings;
 
-    let settings = Settings::new();
+    let settings = Settings::new("io.github.seadve.test");
 
-    assert_eq!(settings.alert_sound(), AlertSound::Bark);
+    let err = settings.try_opacity().unwrap_err();
+    assert!(err.message.contains("invalid percent"));
 
-    settings.set_alert_sound(AlertSound::Glass);
-    assert_eq!(settings.alert_sound(), AlertSound::Glass);
+    settings.set_opacity(&Percent(50));
+    assert_eq!(settings.opacity(), Percent(50));
+    assert_eq!(settings.get::<u8>("opacity"), 50);
 
-    settings.set_alert_sound(AlertSound::Drip);
-    assert_eq!(settings.alert_sound(), AlertSound::Drip);
+    let err = settings.try_set_opacity(&Percent(150)).unwrap_err();
+    assert!(err.message.contains("invalid percent `150`"));
+    assert_eq!(settings.opacity(), Percent(50));
+
+    let err = settings.try_offset().unwrap_err();
+    assert!(err
+        .message
+        .contains("expected value of type `u`, found `n`"));
 }
 
+#[cfg(feature = "serde")]
 #[test]
 #[serial_test::serial]
-fn enumeration_value() {
-    setup_schema();
+fn serde_define() {
+    use serde::{Deserialize, Serialize};
 
-    #[gen_settings(
-        file = "./tests/io.github.seadve.test.gschema.xml",
-        id = "io.github.seadve.test"
-    )]
-    #[gen_settings_skip(signature = "(ss)")]
-    #[gen_settings_skip(signature = "ay")]
-    pub struct Settings;
+    setup_schema();
 
-    assert_eq!(AlertSound::Bark as i32, 0);
-    assert_eq!(AlertSound::Glass as i32, 2);
-    assert_eq!(AlertSound::Drip as i32, 1);
-}
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub struct ExportPreset {
+        name: String,
+        quality: u8,
+    }
 
-#[test]
-#[serial_test::serial]
-fn bitflag() {
-    setup_schema();
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub struct RecentFile(String, i64, bool);
 
-    #[gen_settings(
-        file = "./tests/io.github.seadve.test.gschema.xml",
-        id = "io.github.seadve.test"
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_define(
+        key_name = "export-preset",
+        arg_type = "&ExportPreset",
+        ret_type = "ExportPreset",
+        serde = "json"
+    )]
+    #[gen_settings_define(
+        key_name = "recent-files",
+        arg_type = "&[RecentFile]",
+        ret_type = "Vec<RecentFile>",
+        serde = "variant"
+    )]
+    #[gen_settings_define(
+        key_name = "window-state",
+        arg_type = "&HashMap<String, i32>",
+        ret_type = "HashMap<String, i32>",
+        serde = "variant"
     )]
-    #[gen_settings_skip(signature = "(ss)")]
-    #[gen_settings_skip(signature = "ay")]
     pub struct Settings;
 
-    let settings = Settings::new();
+    let settings = Settings::new("io.github.seadve.test");
 
-    assert_eq!(SpaceStyle::static_variant_type(), VariantTy::STRING_ARRAY);
+    let default_preset = ExportPreset {
+        name: "Default".into(),
+        quality: 80,
+    };
+    assert_eq!(settings.export_preset(), default_preset);
+    assert_eq!(settings.export_preset_default_value(), default_preset);
+
+    let preset = ExportPreset {
+        name: "Web".into(),
+        quality: 60,
+    };
+    settings.set_export_preset(&preset);
+    assert_eq!(settings.export_preset(), preset);
+    assert_eq!(
+        settings.string("export-preset"),
+        r#"{"name":"Web","quality":60}"#
+    );
 
-    assert_eq!(settings.space_style(), SpaceStyle::empty());
+    settings
+        .set_string("export-preset", r#"{"name":"Web"}"#)
+        .unwrap();
+    let err = settings.try_export_preset().unwrap_err();
+    assert!(err.message.contains("missing field `quality`"));
 
-    settings.set_space_style(SpaceStyle::BEFORE_COLON);
-    assert_eq!(settings.space_style(), SpaceStyle::BEFORE_COLON);
+    assert_eq!(settings.recent_files(), Vec::new());
 
-    settings.set_space_style(SpaceStyle::BEFORE_COLON | SpaceStyle::BEFORE_COMMA);
+    let recent_files = vec![
+        RecentFile("/home/user/a.txt".into(), 1700000000, true),
+        RecentFile("/home/user/b.txt".into(), 1700000100, false),
+    ];
+    settings.set_recent_files(&recent_files);
+    assert_eq!(settings.recent_files(), recent_files);
     assert_eq!(
-        settings.space_style(),
-        SpaceStyle::BEFORE_COLON | SpaceStyle::BEFORE_COMMA
+        settings
+            .value("recent-files")
+            .get::<Vec<(String, i64, bool)>>(),
+        Some(vec![
+            ("/home/user/a.txt".into(), 1700000000, true),
+            ("/home/user/b.txt".into(), 1700000100, false),
+        ])
     );
 
-    settings.set_space_style(
-        SpaceStyle::BEFORE_COLON | SpaceStyle::BEFORE_COMMA | SpaceStyle::BEFORE_SEMICOLON,
+    let window_state = HashMap::from([("width".to_string(), 800), ("height".to_string(), 600)]);
+    settings.set_window_state(&window_state);
+    assert_eq!(settings.window_state(), window_state);
+    assert_eq!(
+        settings.value("window-state").type_(),
+        VariantTy::new("a{sv}").unwrap()
     );
-    assert_eq!(settings.space_style(), SpaceStyle::all());
+
+    settings.reset_export_preset();
+    settings.reset_recent_files();
+    settings.reset_window_state();
 }
 
 #[test]
 #[serial_test::serial]
-fn bitflag_value() {
+fn named_struct() {
     setup_schema();
 
-    #[gen_settings(
-        file = "./tests/io.github.seadve.test.gschema.xml",
-        id = "io.github.seadve.test"
-    )]
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_struct(key_name = "dimensions", fields = "width, height")]
+    #[gen_settings_struct(key_name = "last-file", name = "FileLocation")]
+    #[gen_settings_struct(signature = "(dd)", fields = "horizontal, vertical")]
     #[gen_settings_skip(signature = "(ss)")]
-    #[gen_settings_skip(signature = "ay")]
     pub struct Settings;
 
-    assert_eq!(SpaceStyle::BEFORE_COLON.bits(), 1);
-    assert_eq!(SpaceStyle::BEFORE_SEMICOLON.bits(), 4);
-    assert_eq!(SpaceStyle::BEFORE_COMMA.bits(), 2);
+    let settings = Settings::new("io.github.seadve.test");
 
-    assert_eq!(SpaceStyle::from_bits(1).unwrap(), SpaceStyle::BEFORE_COLON);
     assert_eq!(
-        SpaceStyle::from_bits(4).unwrap(),
-        SpaceStyle::BEFORE_SEMICOLON
+        settings.dimensions(),
+        Dimensions {
+            width: 10,
+            height: 10
+        }
     );
-    assert_eq!(SpaceStyle::from_bits(2).unwrap(), SpaceStyle::BEFORE_COMMA);
+    settings.set_dimensions(Dimensions {
+        width: 100,
+        height: 200,
+    });
+    assert_eq!(settings.dimensions().width, 100);
+    assert_eq!(settings.dimensions().height, 200);
+    assert_eq!(settings.get::<(i32, i32)>("dimensions"), (100, 200));
 
     assert_eq!(
-        SpaceStyle::from_variant(&["before-comma", "before-colon"].to_variant()),
-        Some(SpaceStyle::BEFORE_COLON | SpaceStyle::BEFORE_COMMA)
-    );
-    assert_eq!(
-        SpaceStyle::from_variant(&["before-comma", "invalid"].to_variant()),
-        None
+        settings.last_file_default_value(),
+        FileLocation {
+            path: String::new(),
+            line: 0,
+            is_modified: false
+        }
     );
+    settings.set_last_file(FileLocation {
+        path: "/tmp/file.txt".into(),
+        line: 42,
+        is_modified: true,
+    });
+    assert_eq!(settings.last_file().path, "/tmp/file.txt");
+    assert_eq!(settings.last_file().line, 42);
+    assert!(settings.last_file().is_modified);
 
-    assert_eq!(
-        SpaceStyle::BEFORE_COLON.to_variant(),
-        ["before-colon"].to_variant()
-    );
-    assert_eq!(
-        (SpaceStyle::BEFORE_COLON | SpaceStyle::BEFORE_SEMICOLON).to_variant(),
-        ["before-colon", "before-semicolon"].to_variant()
-    );
+    settings.set_scale(Scale {
+        horizontal: 2.0,
+        vertical: 0.5,
+    });
+    assert_eq!(settings.scale().horizontal, 2.0);
+    assert_eq!(settings.get::<(f64, f64)>("scale"), (2.0, 0.5));
+
+    settings.reset_dimensions();
+    settings.reset_last_file();
+    settings.reset_scale();
 }
 
 #[test]
 #[serial_test::serial]
-fn id_defined_in_macro() {
+fn adapters() {
+    use std::{
+        fmt,
+        str::FromStr,
+        time::{Duration, SystemTime},
+    };
+
     setup_schema();
 
-    #[gen_settings(
-        file = "./tests/io.github.seadve.test.gschema.xml",
-        id = "io.github.seadve.test"
-    )]
-    #[gen_settings_skip(signature = "(ss)")]
-    #[gen_settings_skip(signature = "ay")]
-    pub struct Settings;
+    #[derive(Debug, PartialEq)]
+    pub struct Rgb(u8, u8, u8);
 
-    assert_eq!(Settings::default().schema_id(), Settings::new().schema_id());
-    assert_eq!(
-        Settings::default().schema_id().as_deref(),
+    impl FromStr for Rgb {
+        type Err = &'static str;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let hex = s.strip_prefix('#').ok_or("missing `#`")?;
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                    .ok_or("invalid channel")
+            };
+            Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+        }
+    }
+
+    impl fmt::Display for Rgb {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+        }
+    }
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_define(
+        key_name = "timeout",
+        arg_type = "Duration",
+        ret_type = "Duration",
+        adapter = "seconds"
+    )]
+    #[gen_settings_define(
+        key_name = "idle-timeout",
+        arg_type = "Duration",
+        ret_type = "Duration",
+        adapter = "seconds"
+    )]
+    #[gen_settings_define(
+        key_name = "last-opened",
+        arg_type = "SystemTime",
+        ret_type = "SystemTime",
+        adapter = "system_time"
+    )]
+    #[gen_settings_define(
+        key_name = "accent-color",
+        arg_type = "&Rgb",
+        ret_type = "Rgb",
+        adapter = "from_str"
+    )]
+    #[gen_settings_define(
+        key_name = "cache-dir",
+        arg_type = "&str",
+        ret_type = "PathBuf",
+        adapter = "path"
+    )]
+    pub struct Settings;
+
+    mod date_time {
+        use super::*;
+
+        #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+        #[gen_settings_define(
+            key_name = "last-opened",
+            arg_type = "&glib::DateTime",
+            ret_type = "glib::DateTime",
+            adapter = "date_time"
+        )]
+        pub struct DateTimeSettings;
+    }
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(settings.timeout(), Duration::from_secs(30));
+    settings.set_timeout(Duration::from_secs(90));
+    assert_eq!(settings.timeout(), Duration::from_secs(90));
+    assert_eq!(settings.uint("timeout"), 90);
+    let err = settings
+        .try_set_timeout(Duration::from_secs(u64::from(u32::MAX) + 1))
+        .unwrap_err();
+    assert!(err.message.contains("out of range"));
+
+    assert_eq!(settings.idle_timeout(), Duration::from_secs(300));
+    settings.set_idle_timeout(Duration::from_secs(u64::from(u32::MAX) + 1));
+    assert_eq!(
+        settings.idle_timeout(),
+        Duration::from_secs(u64::from(u32::MAX) + 1)
+    );
+
+    assert_eq!(settings.last_opened(), SystemTime::UNIX_EPOCH);
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000);
+    settings.set_last_opened(time);
+    assert_eq!(settings.last_opened(), time);
+    assert_eq!(settings.int64("last-opened"), 1700000000);
+    let time = SystemTime::UNIX_EPOCH - Duration::from_secs(100);
+    settings.set_last_opened(time);
+    assert_eq!(settings.last_opened(), time);
+    assert_eq!(settings.int64("last-opened"), -100);
+
+    let date_time_settings = date_time::DateTimeSettings::new("io.github.seadve.test");
+    let date_time = glib::DateTime::from_unix_local(1800000000).unwrap();
+    date_time_settings.set_last_opened(&date_time);
+    assert_eq!(date_time_settings.last_opened(), date_time);
+    assert_eq!(settings.int64("last-opened"), 1800000000);
+
+    assert_eq!(settings.accent_color(), Rgb(0x35, 0x84, 0xe4));
+    settings.set_accent_color(&Rgb(0xff, 0x00, 0x80));
+    assert_eq!(settings.accent_color(), Rgb(0xff, 0x00, 0x80));
+    assert_eq!(settings.string("accent-color"), "#ff0080");
+    settings.set_string("accent-color", "red").unwrap();
+    let err = settings.try_accent_color().unwrap_err();
+    assert!(err.message.contains("missing `#`"));
+
+    assert_eq!(settings.cache_dir(), PathBuf::from("/tmp/cache_dir/"));
+    settings.set_cache_dir("/var/cache/app");
+    assert_eq!(settings.cache_dir(), PathBuf::from("/var/cache/app"));
+
+    settings.reset_timeout();
+    settings.reset_idle_timeout();
+    settings.reset_last_opened();
+    settings.reset_accent_color();
+    settings.reset_cache_dir();
+}
+
+#[cfg(feature = "uri")]
+#[test]
+#[serial_test::serial]
+fn uri_adapter() {
+    setup_schema();
+
+    #[gen_settings(file = "tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_define(
+        key_name = "homepage",
+        arg_type = "&glib::Uri",
+        ret_type = "glib::Uri",
+        adapter = "uri"
+    )]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(settings.homepage().to_str(), "https://example.org/");
+    let uri = glib::Uri::parse("https://gnome.org/apps?q=settings", glib::UriFlags::NONE).unwrap();
+    settings.set_homepage(&uri);
+    assert_eq!(settings.homepage().host().unwrap(), "gnome.org");
+    assert_eq!(
+        settings.string("homepage"),
+        "https://gnome.org/apps?q=settings"
+    );
+    settings.set_string("homepage", "not a uri").unwrap();
+    assert!(settings.try_homepage().is_err());
+
+    settings.reset_homepage();
+}
+
+#[test]
+#[serial_test::serial]
+fn overlapping_define() {
+    setup_schema();
+
+    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_define(signature = "ay", arg_type = "&OsStr", ret_type = "OsString")]
+    #[gen_settings_define(key_name = "cache-dir", arg_type = "&Path", ret_type = "PathBuf")]
+    #[gen_settings_skip(signature = "(ss)")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+    settings.set_cache_dir(Path::new("/some_dir"));
+    assert_eq!(settings.cache_dir(), PathBuf::from("/some_dir"));
+}
+
+#[test]
+#[serial_test::serial]
+fn string_choice_enum() {
+    setup_schema();
+
+    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    assert_eq!(
+        PreferredAudioSource::static_variant_type(),
+        gio::glib::VariantTy::STRING
+    );
+
+    assert_eq!(
+        PreferredAudioSource::DesktopAudio
+            .to_variant()
+            .get::<String>()
+            .unwrap(),
+        "desktop-audio"
+    );
+    assert_eq!(
+        PreferredAudioSource::Microphone
+            .to_variant()
+            .get::<String>()
+            .unwrap(),
+        "microphone"
+    );
+
+    assert_eq!(
+        PreferredAudioSource::DesktopAudio,
+        PreferredAudioSource::from_variant(&"desktop-audio".to_variant()).unwrap()
+    );
+    assert_eq!(
+        PreferredAudioSource::Microphone,
+        PreferredAudioSource::from_variant(&"microphone".to_variant()).unwrap()
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn enumeration() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.test.gschema.xml",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    let settings = Settings::new();
+
+    assert_eq!(settings.alert_sound(), AlertSound::Bark);
+
+    settings.set_alert_sound(AlertSound::Glass);
+    assert_eq!(settings.alert_sound(), AlertSound::Glass);
+
+    settings.set_alert_sound(AlertSound::Drip);
+    assert_eq!(settings.alert_sound(), AlertSound::Drip);
+}
+
+#[test]
+#[serial_test::serial]
+fn enumeration_value() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.test.gschema.xml",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    assert_eq!(AlertSound::Bark as i32, 0);
+    assert_eq!(AlertSound::Glass as i32, 2);
+    assert_eq!(AlertSound::Drip as i32, 1);
+}
+
+#[test]
+#[serial_test::serial]
+fn bitflag() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.test.gschema.xml",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    let settings = Settings::new();
+
+    assert_eq!(SpacesStyle::static_variant_type(), VariantTy::STRING_ARRAY);
+
+    assert_eq!(settings.space_style(), SpacesStyle::empty());
+
+    settings.set_space_style(SpacesStyle::BEFORE_COLON);
+    assert_eq!(settings.space_style(), SpacesStyle::BEFORE_COLON);
+
+    settings.set_space_style(SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA);
+    assert_eq!(
+        settings.space_style(),
+        SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA
+    );
+
+    settings.set_space_style(
+        SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA | SpacesStyle::BEFORE_SEMICOLON,
+    );
+    assert_eq!(settings.space_style(), SpacesStyle::all());
+}
+
+#[test]
+#[serial_test::serial]
+fn bitflag_value() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.test.gschema.xml",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    assert_eq!(SpacesStyle::BEFORE_COLON.bits(), 1);
+    assert_eq!(SpacesStyle::BEFORE_SEMICOLON.bits(), 4);
+    assert_eq!(SpacesStyle::BEFORE_COMMA.bits(), 2);
+
+    assert_eq!(
+        SpacesStyle::from_bits(1).unwrap(),
+        SpacesStyle::BEFORE_COLON
+    );
+    assert_eq!(
+        SpacesStyle::from_bits(4).unwrap(),
+        SpacesStyle::BEFORE_SEMICOLON
+    );
+    assert_eq!(
+        SpacesStyle::from_bits(2).unwrap(),
+        SpacesStyle::BEFORE_COMMA
+    );
+
+    assert_eq!(
+        SpacesStyle::from_variant(&["before-comma", "before-colon"].to_variant()),
+        Some(SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA)
+    );
+    assert_eq!(
+        SpacesStyle::from_variant(&["before-comma", "invalid"].to_variant()),
+        None
+    );
+
+    assert_eq!(
+        SpacesStyle::BEFORE_COLON.to_variant(),
+        ["before-colon"].to_variant()
+    );
+    assert_eq!(
+        (SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_SEMICOLON).to_variant(),
+        ["before-colon", "before-semicolon"].to_variant()
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn id_defined_in_macro() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.test.gschema.xml",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    assert_eq!(Settings::default().schema_id(), Settings::new().schema_id());
+    assert_eq!(
+        Settings::default().schema_id().as_deref(),
         Some("io.github.seadve.test")
     );
 }
@@ -498,5 +1195,860 @@ fn private_struct() {
     // use inner::AlertSound;
     // use inner::PreferredAudioSource;
     // use inner::Settings;
-    // use inner::SpaceStyle;
+    // use inner::SpacesStyle;
+}
+
+#[test]
+#[serial_test::serial]
+fn shared_enum() {
+    setup_schema();
+
+    #[gen_settings(file = "./tests/io.github.seadve.derive.gschema.xml")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.derive");
+
+    let corner: Corner = settings.corner_default_value();
+    assert_eq!(corner, Corner::TopRightCorner);
+    assert_eq!(settings.other_corner_default_value(), Corner::BottomLeft);
+
+    settings.set_corner(Corner::BottomRight);
+    settings.set_other_corner(settings.corner());
+    assert_eq!(settings.other_corner(), Corner::BottomRight);
+
+    settings.reset_corner();
+    settings.reset_other_corner();
+}
+
+#[test]
+fn standalone_types() {
+    mod types {
+        gsettings_macro::gen_settings_types!(file = "./tests/io.github.seadve.test.gschema.xml");
+    }
+
+    use types::{AlertSound, SpacesStyle};
+
+    assert_eq!(AlertSound::static_variant_type(), VariantTy::STRING);
+    assert_eq!(AlertSound::Glass.to_variant(), "glass".to_variant());
+    assert_eq!(
+        "drip".to_variant().get::<AlertSound>(),
+        Some(AlertSound::Drip)
+    );
+    assert_eq!("purr".to_variant().get::<AlertSound>(), None);
+
+    let style = SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA;
+    assert_eq!(
+        style.to_variant(),
+        vec!["before-colon", "before-comma"].to_variant()
+    );
+    assert_eq!(
+        vec!["before-semicolon"].to_variant().get::<SpacesStyle>(),
+        Some(SpacesStyle::BEFORE_SEMICOLON)
+    );
+}
+
+#[test]
+fn enum_api() {
+    mod types {
+        gsettings_macro::gen_settings_types!(file = "./tests/io.github.seadve.test.gschema.xml");
+    }
+
+    use types::AlertSound;
+
+    assert_eq!(
+        AlertSound::ALL,
+        [AlertSound::Bark, AlertSound::Glass, AlertSound::Drip]
+    );
+    assert_eq!(
+        AlertSound::iter().map(AlertSound::nick).collect::<Vec<_>>(),
+        ["bark", "glass", "drip"]
+    );
+
+    assert_eq!(AlertSound::Drip.nick(), "drip");
+    assert_eq!(AlertSound::from_nick("glass"), Some(AlertSound::Glass));
+    assert_eq!(AlertSound::from_nick("purr"), None);
+
+    assert_eq!(AlertSound::Glass.to_string(), "glass");
+    assert_eq!("drip".parse::<AlertSound>().unwrap(), AlertSound::Drip);
+    assert!("Drip".parse::<AlertSound>().is_err());
+
+    assert_eq!(AlertSound::try_from(2).unwrap(), AlertSound::Glass);
+    assert_eq!(AlertSound::try_from(1).unwrap(), AlertSound::Drip);
+    assert!(AlertSound::try_from(3).is_err());
+
+    assert_eq!(AlertSound::default(), AlertSound::Bark);
+
+    let sounds = AlertSound::iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(sounds.len(), 3);
+
+    assert!(AlertSound::Bark < AlertSound::Drip);
+    assert_eq!(AlertSound::iter().max(), Some(AlertSound::Glass));
+}
+
+#[test]
+fn enum_default_override() {
+    #[gen_settings(
+        xml = r#"
+            <schemalist>
+                <enum id="io.github.seadve.test.Sound">
+                    <value nick="bark" value="0"/>
+                    <value nick="glass" value="1"/>
+                </enum>
+                <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+                    <key name="sound" enum="io.github.seadve.test.Sound">
+                        <default>'bark'</default>
+                    </key>
+                </schema>
+                <schema path="/io/github/seadve/test/quiet/" id="io.github.seadve.test.quiet" extends="io.github.seadve.test">
+                    <override name="sound">'glass'</override>
+                </schema>
+            </schemalist>
+        "#,
+        id = "io.github.seadve.test.quiet"
+    )]
+    pub struct Settings;
+
+    // The keys in the file have different defaults once overridden, so
+    // `Sound` is generated without `Default`, as with `gen_settings_types!`
+    assert_eq!(Sound::ALL, [Sound::Bark, Sound::Glass]);
+}
+
+#[test]
+fn extends_inherits_children() {
+    #[gen_settings(
+        xml = r#"
+            <schemalist>
+                <schema id="io.github.seadve.test.window">
+                    <key name="width" type="i">
+                        <default>100</default>
+                    </key>
+                </schema>
+                <schema id="io.github.seadve.test.profile">
+                    <key name="title" type="s">
+                        <default>'Unnamed'</default>
+                    </key>
+                    <child name="window" schema="io.github.seadve.test.window"/>
+                </schema>
+                <schema path="/io/github/seadve/test/default-profile/" id="io.github.seadve.test.default-profile" extends="io.github.seadve.test.profile">
+                    <override name="title">'Default'</override>
+                </schema>
+            </schemalist>
+        "#,
+        id = "io.github.seadve.test.default-profile"
+    )]
+    #[gen_settings_schema(id = "io.github.seadve.test.window", name = "WindowSettings")]
+    pub struct Settings;
+
+    let _: fn(&Settings) -> WindowSettings = Settings::window;
+}
+
+#[test]
+fn enum_default_shared_override() {
+    #[gen_settings(
+        xml = r#"
+            <schemalist>
+                <enum id="io.github.seadve.test.Sound">
+                    <value nick="bark" value="0"/>
+                    <value nick="glass" value="1"/>
+                </enum>
+                <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+                    <key name="sound" enum="io.github.seadve.test.Sound">
+                        <default>'bark'</default>
+                    </key>
+                </schema>
+                <schema path="/io/github/seadve/test/quiet/" id="io.github.seadve.test.quiet" extends="io.github.seadve.test">
+                    <override name="sound">'bark'</override>
+                </schema>
+            </schemalist>
+        "#,
+        id = "io.github.seadve.test.quiet"
+    )]
+    pub struct Settings;
+
+    assert_eq!(Sound::default(), Sound::Bark);
+}
+
+#[test]
+fn string_choice_enum_conflicting_defaults() {
+    #[gen_settings(
+        xml = r#"
+            <schemalist>
+                <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+                    <key name="theme" type="s">
+                        <choices>
+                            <choice value="light"/>
+                            <choice value="dark"/>
+                        </choices>
+                        <default>'light'</default>
+                    </key>
+                </schema>
+                <schema path="/io/github/seadve/test/night/" id="io.github.seadve.test.night">
+                    <key name="theme" type="s">
+                        <choices>
+                            <choice value="light"/>
+                            <choice value="dark"/>
+                        </choices>
+                        <default>'dark'</default>
+                    </key>
+                </schema>
+            </schemalist>
+        "#,
+        id = "io.github.seadve.test",
+        all_schemas
+    )]
+    pub struct Settings;
+
+    // A single `Theme` is generated for both keys, without `Default`
+    assert_eq!(Theme::ALL, [Theme::Light, Theme::Dark]);
+}
+
+#[test]
+#[serial_test::serial]
+fn string_choice_enum_api() {
+    setup_schema();
+
+    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(
+        PreferredAudioSource::default(),
+        settings.preferred_audio_source_default_value()
+    );
+    assert_eq!(
+        PreferredAudioSource::iter()
+            .map(|source| source.to_string())
+            .collect::<Vec<_>>(),
+        ["microphone", "desktop-audio"]
+    );
+    assert_eq!(
+        PreferredAudioSource::from_nick("desktop-audio"),
+        Some(PreferredAudioSource::DesktopAudio)
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn enum_types() {
+    setup_schema();
+
+    mod model {
+        #[derive(Debug, Clone, Copy, PartialEq, gsettings_macro::SettingsEnum)]
+        pub enum Sound {
+            Bark = 0,
+            Glass = 2,
+            #[nick = "drip"]
+            WaterDrip = 1,
+        }
+
+        gsettings_macro::gen_settings_types!(file = "./tests/io.github.seadve.test.gschema.xml");
+    }
+
+    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml")]
+    #[gen_settings_enum(id = "io.github.seadve.AlertSound", type = "model::Sound")]
+    #[gen_settings_enum(id = "io.github.seadve.SpacesStyle", type = "model::SpacesStyle")]
+    pub struct Settings;
+
+    let settings = Settings::new("io.github.seadve.test");
+
+    assert_eq!(settings.alert_sound(), model::Sound::Bark);
+    settings.set_alert_sound(model::Sound::WaterDrip);
+    assert_eq!(settings.alert_sound(), model::Sound::WaterDrip);
+    assert_eq!(settings.string("alert-sound"), "drip");
+    assert_eq!(settings.enum_("alert-sound"), 1);
+
+    settings.set_space_style(model::SpacesStyle::BEFORE_COMMA);
+    assert_eq!(settings.space_style(), model::SpacesStyle::BEFORE_COMMA);
+
+    assert_eq!(
+        <model::Sound as gsettings_macro::SettingsEnum>::VALUES,
+        &[("bark", 0), ("glass", 2), ("drip", 1)]
+    );
+    assert_eq!(
+        <model::SpacesStyle as gsettings_macro::SettingsFlags>::VALUES,
+        &[
+            ("before-colon", 1),
+            ("before-semicolon", 4),
+            ("before-comma", 2)
+        ]
+    );
+
+    settings.reset_alert_sound();
+    settings.reset_space_style();
+}
+
+#[test]
+#[serial_test::serial]
+fn glib_types() {
+    setup_schema();
+
+    mod player {
+        use gio::glib;
+        use gsettings_macro::gen_settings;
+
+        #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml", glib_types)]
+        pub struct Settings;
+
+        mod imp {
+            use gio::{glib, prelude::*, subclass::prelude::*};
+
+            use std::cell::Cell;
+
+            use super::{AlertSound, SpacesStyle};
+
+            #[derive(glib::Properties)]
+            #[properties(wrapper_type = super::Player)]
+            pub struct Player {
+                #[property(get, set, builder(AlertSound::Bark))]
+                alert_sound: Cell<AlertSound>,
+                #[property(get, set)]
+                space_style: Cell<SpacesStyle>,
+            }
+
+            impl Default for Player {
+                fn default() -> Self {
+                    Self {
+                        alert_sound: Cell::new(AlertSound::Bark),
+                        space_style: Cell::new(SpacesStyle::empty()),
+                    }
+                }
+            }
+
+            #[glib::object_subclass]
+            impl ObjectSubclass for Player {
+                const NAME: &'static str = "GSettingsMacroTestPlayer";
+                type Type = super::Player;
+            }
+
+            #[glib::derived_properties]
+            impl ObjectImpl for Player {}
+        }
+
+        glib::wrapper! {
+            pub struct Player(ObjectSubclass<imp::Player>);
+        }
+    }
+
+    use player::{AlertSound, Player, Settings, SpacesStyle};
+
+    assert_eq!(AlertSound::Glass.to_value().get(), Ok(AlertSound::Glass));
+    let enum_class = glib::EnumClass::new::<AlertSound>();
+    assert_eq!(enum_class.value_by_nick("drip").unwrap().value(), 1);
+    let flags_class = glib::FlagsClass::new::<SpacesStyle>();
+    assert_eq!(
+        flags_class
+            .value_by_nick("before-semicolon")
+            .unwrap()
+            .value(),
+        4
+    );
+
+    let settings = Settings::new("io.github.seadve.test");
+    let player = glib::Object::new::<Player>();
+
+    settings.bind_alert_sound(&player, "alert-sound").build();
+    settings.bind_space_style(&player, "space-style").build();
+
+    settings.set_alert_sound(AlertSound::Glass);
+    assert_eq!(player.alert_sound(), AlertSound::Glass);
+
+    player.set_space_style(SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA);
+    assert_eq!(
+        settings.space_style(),
+        SpacesStyle::BEFORE_COLON | SpacesStyle::BEFORE_COMMA
+    );
+
+    settings.reset_alert_sound();
+    settings.reset_space_style();
+}
+
+#[test]
+#[serial_test::serial]
+fn multiple_schemas() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.multi.gschema.xml",
+        id = "io.github.seadve.multi"
+    )]
+    #[gen_settings_schema(id = "io.github.seadve.multi.window", name = "WindowState")]
+    pub struct Settings;
+
+    let settings = Settings::new();
+    assert_eq!(settings.corner(), Corner::TopLeft);
+    settings.set_is_enabled(false);
+    assert!(!settings.is_enabled());
+
+    let window_state = WindowState::default();
+    assert_eq!(
+        window_state.schema_id().as_deref(),
+        Some("io.github.seadve.multi.window")
+    );
+    assert_eq!(window_state.corner(), Corner::BottomRight);
+    window_state.set_width(200);
+    assert_eq!(window_state.width(), 200);
+}
+
+#[test]
+#[serial_test::serial]
+fn all_schemas() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.multi.gschema.xml",
+        id = "io.github.seadve.multi",
+        all_schemas
+    )]
+    #[gen_settings_schema(id = "io.github.seadve.multi.window", name = "WindowState")]
+    pub struct Settings;
+
+    assert_eq!(
+        Settings::new().schema_id().as_deref(),
+        Some("io.github.seadve.multi")
+    );
+    assert_eq!(
+        WindowState::new().schema_id().as_deref(),
+        Some("io.github.seadve.multi.window")
+    );
+    assert_eq!(
+        PluginSettings::new().schema_id().as_deref(),
+        Some("io.github.seadve.multi.plugin")
+    );
+    assert_eq!(PluginSettings::new().name(), "none");
+}
+
+#[test]
+#[serial_test::serial]
+fn child_schemas() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.multi.gschema.xml",
+        id = "io.github.seadve.multi"
+    )]
+    #[gen_settings_schema(id = "io.github.seadve.multi.window", name = "WindowState")]
+    pub struct Settings;
+
+    let settings = Settings::new();
+
+    let window_state: WindowState = settings.window();
+    assert_eq!(
+        window_state.path().as_deref(),
+        Some("/io/github/seadve/multi/window/")
+    );
+    window_state.set_width(300);
+    assert_eq!(WindowState::new().width(), 300);
+
+    let plugin_settings: gio::Settings = settings.plugin();
+    assert_eq!(
+        plugin_settings.schema_id().as_deref(),
+        Some("io.github.seadve.multi.plugin")
+    );
+    assert_eq!(plugin_settings.string("name"), "none");
+}
+
+#[test]
+#[serial_test::serial]
+fn extends_schema() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.multi.gschema.xml",
+        id = "io.github.seadve.multi.default-profile"
+    )]
+    #[gen_settings_schema(id = "io.github.seadve.multi.profile", name = "Profile")]
+    pub struct DefaultProfile;
+
+    fn increase_font_size(profile: &impl ProfileKeys) {
+        profile.set_font_size(profile.font_size() + 1);
+    }
+
+    let default_profile = DefaultProfile::new();
+    assert_eq!(default_profile.title_default_value(), "Default");
+    assert_eq!(default_profile.title(), "Default");
+    assert_eq!(
+        ProfileKeys::title_default_value(&default_profile),
+        "Default"
+    );
+    assert_eq!(default_profile.font_size(), 12);
+    assert!(!default_profile.is_locked());
+
+    increase_font_size(&default_profile);
+    assert_eq!(default_profile.font_size(), 13);
+}
+
+#[test]
+#[serial_test::serial]
+fn relocatable_schema() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.multi.gschema.xml",
+        id = "io.github.seadve.multi.profile"
+    )]
+    #[gen_settings_schema(id = "io.github.seadve.multi.default-profile")]
+    pub struct Profile;
+
+    assert_eq!(
+        DefaultProfileSettings::PATH,
+        "/io/github/seadve/multi/default-profile/"
+    );
+
+    let base_path = "/io/github/seadve/multi/profiles/";
+    assert_eq!(
+        Profile::path_for_id(base_path, "first"),
+        "/io/github/seadve/multi/profiles/first/"
+    );
+
+    let first = Profile::new_with_path(&Profile::path_for_id(base_path, "first"));
+    let second = Profile::new_with_path(&Profile::path_for_id(base_path, "second"));
+    assert_eq!(
+        first.path().as_deref(),
+        Some("/io/github/seadve/multi/profiles/first/")
+    );
+
+    first.set_title("First");
+    second.set_title("Second");
+    assert_eq!(first.title(), "First");
+    assert_eq!(second.title(), "Second");
+}
+
+#[test]
+#[serial_test::serial]
+#[should_panic(expected = "invalid path")]
+fn relocatable_schema_invalid_path() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.multi.gschema.xml",
+        id = "io.github.seadve.multi.profile"
+    )]
+    pub struct Profile;
+
+    Profile::new_with_path("/io/github/seadve//profile");
+}
+
+#[test]
+#[serial_test::serial]
+fn template_substitution() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "./tests/io.github.seadve.template.gschema.xml.in",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_substitute(placeholder = "APP_ID", value = "io.github.seadve.test")]
+    #[gen_settings_substitute(placeholder = "gettext-package", env = "CARGO_PKG_NAME")]
+    pub struct Settings;
+
+    let settings = Settings::new();
+    assert_eq!(
+        settings.schema_id().as_deref(),
+        Some("io.github.seadve.test")
+    );
+
+    settings.set_theme("dark");
+    assert_eq!(settings.theme(), "dark");
+}
+
+#[test]
+fn placeholder_like_text_without_substitution() {
+    #[gen_settings(xml = r#"
+            <schemalist>
+                <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+                    <key name="handle" type="s">
+                        <default>'@user@'</default>
+                        <summary>The handle, like @user@</summary>
+                    </key>
+                </schema>
+            </schemalist>
+        "#)]
+    pub struct Settings;
+
+    // Not a template, so `@user@` is left as is
+    assert!(Settings::SCHEMA_XML.contains("like @user@"));
+}
+
+#[test]
+#[serial_test::serial]
+fn id_defined_as_const() {
+    setup_schema();
+
+    const APP_ID: &str = "io.github.seadve.test";
+
+    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml", id = APP_ID)]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    assert_eq!(Settings::default().schema_id().as_deref(), Some(APP_ID));
+}
+
+#[test]
+#[serial_test::serial]
+#[should_panic(expected = "does not match the schema `io.github.seadve.test`")]
+fn id_defined_as_mismatched_const() {
+    setup_schema();
+
+    const APP_ID: &str = "io.github.seadve.test.Devel";
+
+    #[gen_settings(file = "./tests/io.github.seadve.test.gschema.xml", id = APP_ID)]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    Settings::new();
+}
+
+#[test]
+fn id_defined_with_env() {
+    // Cargo sets `CARGO_PKG_NAME` to `gsettings-macro`
+    #[gen_settings(
+        xml = r#"
+            <schemalist>
+                <schema path="/io/github/seadve/test/" id="gsettings-macro">
+                    <key name="window-width" type="i">
+                        <default>600</default>
+                    </key>
+                </schema>
+                <schema path="/io/github/seadve/other/" id="io.github.seadve.other">
+                    <key name="window-height" type="i">
+                        <default>400</default>
+                    </key>
+                </schema>
+            </schemalist>
+        "#,
+        id = env!("CARGO_PKG_NAME")
+    )]
+    pub struct Settings;
+
+    // The schema selected by the id is used instead of the last one
+    let _ = Settings::window_width;
+    assert_eq!(Settings::PATH, "/io/github/seadve/test/");
+}
+
+#[test]
+#[serial_test::serial]
+fn file_path_with_manifest_dir_prefix() {
+    setup_schema();
+
+    #[gen_settings(
+        file = "$CARGO_MANIFEST_DIR/tests/io.github.seadve.test.gschema.xml",
+        id = "io.github.seadve.test"
+    )]
+    #[gen_settings_skip(signature = "(ss)")]
+    #[gen_settings_skip(signature = "ay")]
+    pub struct Settings;
+
+    assert_eq!(
+        Settings::new().schema_id().as_deref(),
+        Some("io.github.seadve.test")
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn inline_xml() {
+    setup_schema();
+
+    #[gen_settings(
+        xml = r#"
+            <schemalist>
+                <schema path="/io/github/seadve/test/" id="io.github.seadve.test">
+                    <key name="window-width" type="i">
+                        <default>600</default>
+                        <summary>Window width</summary>
+                    </key>
+                </schema>
+            </schemalist>
+        "#,
+        id = "io.github.seadve.test"
+    )]
+    pub struct Settings;
+
+    let settings = Settings::new();
+    settings.set_window_width(200);
+    assert_eq!(settings.window_width(), 200);
+
+    let dir = env::temp_dir();
+    let path = Settings::write_schema_file(&dir).unwrap();
+    assert_eq!(path, dir.join("io.github.seadve.test.gschema.xml"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        Settings::SCHEMA_XML
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "build")]
+#[test]
+fn build_write_schema_files() {
+    let dir = env::temp_dir().join(format!(
+        "gsettings-macro-build_write_schema_files-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("settings.rs");
+    std::fs::write(
+        &source,
+        r##"
+            mod settings {
+                #[gen_settings(
+                    xml = r#"
+                        <schemalist>
+                            <schema id="io.github.seadve.other"/>
+                            <schema path="/io/github/seadve/test/" id="@APP_ID@">
+                                <key name="window-width" type="i">
+                                    <default>600</default>
+                                </key>
+                            </schema>
+                        </schemalist>
+                    "#
+                )]
+                #[gen_settings_substitute(placeholder = "APP_ID", value = "io.github.seadve.test")]
+                pub struct Settings;
+            }
+
+            #[gen_settings(file = "data/io.github.seadve.file.gschema.xml")]
+            pub struct FileSettings;
+        "##,
+    )
+    .unwrap();
+
+    let paths = gsettings_macro::build::write_schema_files(&source, &dir).unwrap();
+    assert_eq!(paths, [dir.join("io.github.seadve.test.gschema.xml")]);
+    let xml = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(xml.contains(r#"id="io.github.seadve.test""#));
+    assert!(!xml.contains("@APP_ID@"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "build")]
+#[test]
+fn build_unsubstituted_placeholder() {
+    let dir = env::temp_dir().join(format!(
+        "gsettings-macro-build_unsubstituted_placeholder-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("settings.rs");
+    std::fs::write(
+        &source,
+        r##"
+            #[gen_settings(
+                xml = r#"
+                    <schemalist gettext-domain="@GETTEXT_PACKAGE@">
+                        <schema path="/io/github/seadve/test/" id="@APP_ID@"/>
+                    </schemalist>
+                "#
+            )]
+            #[gen_settings_substitute(placeholder = "APP_ID", value = "io.github.seadve.test")]
+            pub struct Settings;
+        "##,
+    )
+    .unwrap();
+
+    let err = gsettings_macro::build::write_schema_files(&source, &dir).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err
+        .to_string()
+        .contains("`@GETTEXT_PACKAGE@` is not substituted"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+#[serial_test::serial]
+fn derive_schema() {
+    use gsettings_macro::GSettingsSchema;
+
+    setup_schema();
+
+    #[derive(Debug, Clone, Copy, PartialEq, GSettingsSchema)]
+    #[gsettings(id = "io.github.seadve.derive.Corner")]
+    pub enum Corner {
+        TopLeft,
+        #[gsettings(nick = "top-right-corner")]
+        TopRight,
+        BottomLeft = 4,
+        BottomRight,
+        Hidden = -2147483648,
+    }
+
+    #[derive(Debug, PartialEq, GSettingsSchema)]
+    #[gsettings(id = "io.github.seadve.derive", path = "/io/github/seadve/derive/")]
+    pub struct Preferences {
+        #[gsettings(
+            default = "600",
+            summary = "Window width",
+            description = "Width of the window in <b>pixels</b>",
+            min = "100",
+            max = "4000"
+        )]
+        window_width: i32,
+        #[gsettings(default = "false")]
+        is_maximized: bool,
+        #[gsettings(default = "'light'", choices = ["light", "dark"])]
+        theme: String,
+        #[gsettings(default = "['Monday']", name = "work-days")]
+        days: Vec<String>,
+        #[gsettings(default = "(0, 0)", signature = "(ii)")]
+        position: (i32, i32),
+        #[gsettings(default = "'top-right-corner'")]
+        corner: Corner,
+        #[gsettings(default = "'bottom-left'")]
+        other_corner: Corner,
+    }
+
+    assert_eq!(Preferences::SCHEMA_ID, "io.github.seadve.derive");
+    assert_eq!(
+        Preferences::schema_xml(),
+        include_str!("io.github.seadve.derive.gschema.xml")
+    );
+
+    let settings = PreferencesSettings::new();
+    assert_eq!(PreferencesSettings::PATH, "/io/github/seadve/derive/");
+
+    assert_eq!(settings.window_width(), 600);
+    settings.set_window_width(200);
+    assert_eq!(settings.window_width(), 200);
+
+    assert_eq!(settings.theme(), "light");
+    settings.set_theme("dark");
+    assert_eq!(settings.theme(), "dark");
+
+    assert_eq!(settings.work_days(), vec!["Monday".to_string()]);
+    settings.set_work_days(&["Friday"]);
+    assert_eq!(settings.work_days(), vec!["Friday".to_string()]);
+
+    settings.set_position((2, 3));
+    assert_eq!(settings.position(), (2, 3));
+
+    assert_eq!(settings.corner(), Corner::TopRight);
+    assert_eq!(settings.other_corner(), Corner::BottomLeft);
+    settings.set_corner(Corner::BottomRight);
+    assert_eq!(settings.corner(), Corner::BottomRight);
+    assert_eq!(settings.string("corner"), "bottom-right");
+    settings.set_corner(Corner::Hidden);
+    assert_eq!(settings.enum_("corner"), i32::MIN);
+
+    let preferences = Preferences {
+        window_width: 800,
+        is_maximized: true,
+        theme: "dark".to_string(),
+        days: vec!["Saturday".to_string(), "Sunday".to_string()],
+        position: (-4, 5),
+        corner: Corner::TopLeft,
+        other_corner: Corner::Hidden,
+    };
+    preferences.store(&settings).unwrap();
+    assert_eq!(settings.window_width(), 800);
+    assert!(settings.is_maximized());
+    assert_eq!(settings.other_corner(), Corner::Hidden);
+    assert_eq!(Preferences::load(&settings).unwrap(), preferences);
+
+    for key in settings.settings_schema().unwrap().list_keys() {
+        settings.reset(&key);
+    }
+    assert_eq!(Preferences::load(&settings).unwrap().window_width, 600);
 }